@@ -2,6 +2,8 @@ mod structs;
 use crate::structs::*;
 mod errors;
 
+use std::io::BufRead;
+
 pub fn solve_puzzle1(input: &str) -> i32 {
     let doc = ImprovedCalibrationDocument::from(input);
     doc.sum_calibration_values()
@@ -12,9 +14,19 @@ pub fn solve_puzzle2(input: &str) -> i32 {
     doc.parse_written_digits().sum_calibration_values()
 }
 
+/// Sums the calibration values of both parts one line at a time
+pub fn solve_streaming(reader: impl BufRead) -> (i32, i32) {
+    ImprovedCalibrationDocument::sum_lines(reader)
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if aoc::run_streaming(&args, solve_streaming) {
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -53,4 +65,13 @@ mod tests {
 
         assert_eq!(solve_puzzle2(EXAMPLE_INPUT), EXAMPLE_OUTPUT)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_solve_streaming() {
+        const EXAMPLE_INPUT: &str = "two1nine\nxtwone3four\n4nineeightseven2\ntreb7uchet\n";
+
+        let result = solve_streaming(EXAMPLE_INPUT.as_bytes());
+        assert_eq!(result, (solve_puzzle1(EXAMPLE_INPUT), solve_puzzle2(EXAMPLE_INPUT)));
+        assert_eq!(result, (11 + 33 + 42 + 77, 29 + 24 + 42 + 77));
+    }
+}
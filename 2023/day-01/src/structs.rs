@@ -1,3 +1,5 @@
+use std::io::BufRead;
+
 use crate::errors::ParseCalibrationValueError;
 
 #[derive(Clone)]
@@ -79,4 +81,16 @@ impl ImprovedCalibrationDocument {
             .collect::<Vec<ImprovedCalibrationValue>>();
         ImprovedCalibrationDocument(new_value)
     }
+
+    /// Sums the calibration values with and without written digits
+    /// line by line, without keeping the document in memory
+    pub fn sum_lines(reader: impl BufRead) -> (i32, i32) {
+        let mut sums = (0, 0);
+        for line in reader.lines() {
+            let mut value = ImprovedCalibrationValue(line.expect("failed to read line"));
+            sums.0 += value.recover_calibration_value().unwrap();
+            sums.1 += value.parse_written_digits().recover_calibration_value().unwrap();
+        }
+        sums
+    }
 }
\ No newline at end of file
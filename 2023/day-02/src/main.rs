@@ -1,6 +1,8 @@
 mod structs;
 use crate::structs::*;
 
+use std::io::BufRead;
+
 pub fn solve_puzzle1(input: &str) -> u32 {
     let total_cubes: Sample = Sample::new(12, 13, 14);
 
@@ -23,9 +25,63 @@ pub fn solve_puzzle2(input: &str) -> u32 {
         .sum::<u32>()
 }
 
+/// Adds up the ids of the possible games and the powers of their
+/// minimum sets of cubes, one game at a time
+pub fn solve_streaming(reader: impl BufRead) -> (u32, u32) {
+    let total_cubes: Sample = Sample::new(12, 13, 14);
+    let mut solutions = (0, 0);
+
+    for line in reader.lines() {
+        let game = Game::try_from(line.expect("failed to read line").as_str()).unwrap();
+        let minimum_set_of_cubes = game.minimum_set_of_cubes();
+        if total_cubes.contains(minimum_set_of_cubes.clone()) {
+            solutions.0 += game.id;
+        }
+        solutions.1 += minimum_set_of_cubes.power();
+    }
+
+    solutions
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    #[cfg(feature = "serde")]
+    if std::env::args().any(|arg| arg == "--json") {
+        println!("{}", Games::from(INPUT).to_json());
+        return;
+    }
+
+    let args: Vec<String> = std::env::args().collect();
+    if aoc::run_streaming(&args, solve_streaming) {
+        return;
+    }
+
+    if let Some(i) = args.iter().position(|arg| arg == "--report") {
+        // Optionally takes the number of games the smallest bag should make possible
+        let games = Games::from(INPUT);
+        let n_games: usize = args
+            .get(i + 1)
+            .map(|n| { n.parse().expect("number of games should be parseable as usize") })
+            .unwrap_or(games.0.len());
+        let bag = Sample::new(12, 13, 14);
+
+        println!("Bag: {}", bag);
+        for report in games.report(&bag) {
+            println!("{}", report);
+        }
+        println!("Maximum cubes seen: {}", games.max_cubes());
+        println!("Impossible games: {:?}", games.impossible_game_ids(&bag));
+        match games.smallest_bag_for(n_games) {
+            Some(smallest_bag) => println!(
+                "Smallest bag for {} games: {} ({} cubes)",
+                n_games, smallest_bag, smallest_bag.total()
+            ),
+            None => println!("There are fewer than {} games", n_games),
+        }
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -62,4 +118,16 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_solve_streaming() {
+        const EXAMPLE_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        let result = solve_streaming(EXAMPLE_INPUT.as_bytes());
+        assert_eq!(result, (8, 2286));
+    }
+}
@@ -1,5 +1,9 @@
 use std::{fmt::Display, ops::Deref};
-#[derive(Debug, PartialEq, Eq)]
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Sample {
     red: u32,
     green: u32,
@@ -10,6 +14,7 @@ pub struct Sample {
 pub struct ParseSampleError;
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Game {
     pub id: u32,
     pub samples: Vec<Sample>
@@ -19,8 +24,27 @@ pub struct Game {
 pub struct ParseGameError;
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Games(pub Vec<Game>);
 
+/// A draw that could not have been taken
+/// out of a given bag
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImpossibleDraw {
+    pub game_id: u32,
+    pub sample_index: usize,
+    pub deficit: Sample,
+}
+
+/// Summary of a single game with respect
+/// to a given bag
+#[derive(Debug, PartialEq, Eq)]
+pub struct GameReport {
+    pub game_id: u32,
+    pub minimum_set: Sample,
+    pub impossible_draws: Vec<ImpossibleDraw>,
+}
+
 
 impl Display for ParseSampleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -34,6 +58,24 @@ impl Display for ParseGameError {
     }
 }
 
+impl Display for Sample {
+    /// Writes the sample in the puzzle input format,
+    /// e.g. "4 red, 3 blue". Colors without cubes are
+    /// left out unless the sample is empty
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let colors = [("red", self.red), ("green", self.green), ("blue", self.blue)];
+        let mut counts = colors
+            .iter()
+            .filter(|(_, amount)| { *amount > 0 })
+            .map(|(color, amount)| { format!("{} {}", amount, color) })
+            .collect::<Vec<String>>();
+        if counts.is_empty() {
+            counts.push(String::from("0 red"));
+        }
+        write!(f, "{}", counts.join(", "))
+    }
+}
+
 impl TryFrom<&str> for Sample {
     type Error = ParseSampleError;
 
@@ -104,6 +146,21 @@ impl Sample {
     pub fn power(&self) -> u32 {
         self.red * self.green * self.blue
     }
+
+    /// The number of cubes of all colors together
+    pub fn total(&self) -> u32 {
+        self.red + self.green + self.blue
+    }
+
+    /// The number of cubes of each color that are
+    /// missing from self to contain the given sample
+    pub fn deficit(&self, sample: &Sample) -> Sample {
+        Sample {
+            red: sample.red.saturating_sub(self.red),
+            green: sample.green.saturating_sub(self.green),
+            blue: sample.blue.saturating_sub(self.blue),
+        }
+    }
 }
 
 impl TryFrom<&str> for Game {
@@ -135,12 +192,69 @@ impl TryFrom<&str> for Game {
     }
 }
 
+impl Display for Game {
+    /// Writes the game in the puzzle input format, e.g.
+    /// "Game 1: 4 red, 3 blue; 1 red, 2 green, 6 blue"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let samples = self.samples
+            .iter()
+            .map(|sample| { sample.to_string() })
+            .collect::<Vec<String>>();
+        write!(f, "Game {}: {}", self.id, samples.join("; "))
+    }
+}
+
 impl Game {
     /// The minimum set of cubes required in the 
     /// bag to produce this game
     pub fn minimum_set_of_cubes(&self) -> Sample {
         Sample::union(&self.samples)
     }
+
+    /// Returns every draw of this game that
+    /// could not have been taken out of the bag
+    pub fn impossible_draws(&self, bag: &Sample) -> Vec<ImpossibleDraw> {
+        self.samples
+            .iter()
+            .enumerate()
+            .filter(|(_, sample)| { !bag.contains((*sample).clone()) })
+            .map(|(i, sample)| {
+                ImpossibleDraw { game_id: self.id, sample_index: i, deficit: bag.deficit(sample) }
+            })
+            .collect()
+    }
+}
+
+impl Display for ImpossibleDraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "draw {} of game {} is short of {}", self.sample_index + 1, self.game_id, self.deficit)
+    }
+}
+
+impl GameReport {
+    pub fn is_possible(&self) -> bool {
+        self.impossible_draws.is_empty()
+    }
+}
+
+impl Display for GameReport {
+    /// Writes a one line summary followed by
+    /// one indented line per impossible draw
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.is_possible() { "possible" } else { "impossible" };
+        write!(
+            f,
+            "Game {}: {}, minimum set: {}, power: {}",
+            self.game_id,
+            status,
+            self.minimum_set,
+            self.minimum_set.power()
+        )?;
+        for draw in &self.impossible_draws {
+            write!(f, "\n    {}", draw)?;
+        }
+        Ok(())
+    }
 }
 
 impl From<&str> for Games {
@@ -154,7 +268,23 @@ impl From<&str> for Games {
     }
 }
 
+impl Display for Games {
+    /// Writes one game per line, just like the puzzle input
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for game in &self.0 {
+            writeln!(f, "{}", game)?;
+        }
+        Ok(())
+    }
+}
+
 impl Games {
+    /// Serializes all games into a JSON array
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("games should always be serializable to JSON")
+    }
+
     /// Returns a vector of games that would be possible with
     /// a given set of cubes in the entire bag
     pub fn possible_games_with(&self, sample: Sample) -> Vec<&Game> {
@@ -164,12 +294,107 @@ impl Games {
             })
             .collect() 
     }
+
+    /// The maximum number of cubes of each color seen in
+    /// any draw of any game, no cubes at all without games
+    pub fn max_cubes(&self) -> Sample {
+        self.0
+            .iter()
+            .map(|game| { game.minimum_set_of_cubes() })
+            .fold(Sample::new(0, 0, 0), |max, minimum_set| Sample::union(&vec![max, minimum_set]))
+    }
+
+    /// Returns every draw of every game that could
+    /// not have been taken out of the given bag
+    pub fn impossible_draws(&self, bag: &Sample) -> Vec<ImpossibleDraw> {
+        self.0.iter()
+            .flat_map(|game| { game.impossible_draws(bag) })
+            .collect()
+    }
+
+    /// Returns the ids of all games that would
+    /// be impossible with the given bag
+    pub fn impossible_game_ids(&self, bag: &Sample) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .impossible_draws(bag)
+            .into_iter()
+            .map(|draw| { draw.game_id })
+            .collect();
+        ids.dedup();
+        ids
+    }
+
+    /// Summarizes every game with respect to the given bag
+    pub fn report(&self, bag: &Sample) -> Vec<GameReport> {
+        self.0.iter()
+            .map(|game| {
+                GameReport {
+                    game_id: game.id,
+                    minimum_set: game.minimum_set_of_cubes(),
+                    impossible_draws: game.impossible_draws(bag),
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the bag with the fewest cubes in total that
+    /// makes at least n games possible. Ties are broken by
+    /// the lower power. Returns None if there are fewer
+    /// than n games
+    pub fn smallest_bag_for(&self, n: usize) -> Option<Sample> {
+        if n > self.0.len() {
+            return None
+        }
+        if n == 0 {
+            return Some(Sample::new(0, 0, 0))
+        }
+
+        let minimum_sets: Vec<Sample> = self.0
+            .iter()
+            .map(|game| { game.minimum_set_of_cubes() })
+            .collect();
+
+        // An optimal bag only ever contains as many cubes of
+        // a color as some game needs at minimum
+        let mut reds: Vec<u32> = minimum_sets.iter().map(|sample| { sample.red }).collect();
+        let mut greens: Vec<u32> = minimum_sets.iter().map(|sample| { sample.green }).collect();
+        reds.sort_unstable();
+        reds.dedup();
+        greens.sort_unstable();
+        greens.dedup();
+
+        let mut best: Option<Sample> = None;
+        for &red in &reds {
+            for &green in &greens {
+                let mut blues: Vec<u32> = minimum_sets
+                    .iter()
+                    .filter(|sample| { sample.red <= red && sample.green <= green })
+                    .map(|sample| { sample.blue })
+                    .collect();
+                if blues.len() < n {
+                    continue
+                }
+                blues.sort_unstable();
+                let bag = Sample::new(red, green, blues[n - 1]);
+
+                let is_better = match &best {
+                    None => true,
+                    Some(best) => (bag.total(), bag.power()) < (best.total(), best.power()),
+                };
+                if is_better {
+                    best = Some(bag);
+                }
+            }
+        }
+        best
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_sample_from_string() {
@@ -205,4 +430,132 @@ mod tests {
         let result = input.minimum_set_of_cubes();
         assert_eq!(result, output)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sample_display() {
+        let inputs = vec![Sample::new(4, 0, 3), Sample::new(0, 2, 0), Sample::new(0, 0, 0)];
+        let outputs = vec!["4 red, 3 blue", "2 green", "0 red"];
+        for (input, output) in inputs.into_iter().zip(outputs) {
+            assert_eq!(input.to_string(), output);
+        }
+    }
+
+    #[test]
+    fn test_game_display() {
+        const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        const OUTPUT: &str = "Game 1: 4 red, 3 blue; 1 red, 2 green, 6 blue; 2 green";
+        let result = Game::try_from(INPUT).unwrap().to_string();
+        assert_eq!(result, OUTPUT)
+    }
+
+    #[test]
+    fn test_games_display_round_trip() {
+        const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        let games = Games::from(INPUT);
+        for game in &games.0 {
+            let result = Game::try_from(game.to_string().as_str()).unwrap();
+            assert_eq!(&result, game);
+        }
+        let result = Games::from(games.to_string().as_str());
+        assert_eq!(result, games);
+    }
+
+    #[test]
+    fn test_games_max_cubes() {
+        const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+";
+        let result = Games::from(INPUT).max_cubes();
+        assert_eq!(result, Sample::new(20, 13, 6))
+    }
+
+    #[test]
+    fn test_games_empty() {
+        let games = Games::from("");
+        let bag = Sample::new(12, 13, 14);
+        assert_eq!(games.max_cubes(), Sample::new(0, 0, 0));
+        assert_eq!(games.report(&bag), vec![]);
+        assert_eq!(games.smallest_bag_for(0), Some(Sample::new(0, 0, 0)));
+        assert_eq!(games.smallest_bag_for(1), None);
+    }
+
+    #[test]
+    fn test_games_impossible_draws() {
+        const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+";
+        let bag = Sample::new(12, 13, 14);
+        let output = vec![
+            ImpossibleDraw { game_id: 3, sample_index: 0, deficit: Sample::new(8, 0, 0) },
+            ImpossibleDraw { game_id: 4, sample_index: 2, deficit: Sample::new(2, 0, 1) },
+        ];
+        let games = Games::from(INPUT);
+        assert_eq!(games.impossible_draws(&bag), output);
+        assert_eq!(games.impossible_game_ids(&bag), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_games_smallest_bag_for() {
+        const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        let games = Games::from(INPUT);
+        assert_eq!(games.smallest_bag_for(0), Some(Sample::new(0, 0, 0)));
+        assert_eq!(games.smallest_bag_for(1), Some(Sample::new(1, 3, 4)));
+        assert_eq!(games.smallest_bag_for(3), Some(Sample::new(6, 3, 6)));
+        assert_eq!(games.smallest_bag_for(5), Some(Sample::new(20, 13, 15)));
+        assert_eq!(games.smallest_bag_for(6), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_games_to_json() {
+        let games = Games(vec![Game { id: 3, samples: vec![Sample::new(1, 0, 2)] }]);
+        let output = r#"[{"id":3,"samples":[{"red":1,"green":0,"blue":2}]}]"#;
+        assert_eq!(games.to_json(), output);
+    }
+
+    fn sample() -> impl Strategy<Value = Sample> {
+        (0..100u32, 0..100u32, 0..100u32).prop_map(|(red, green, blue)| Sample::new(red, green, blue))
+    }
+
+    proptest! {
+        #[test]
+        fn test_union_idempotent(samples in prop::collection::vec(sample(), 1..10)) {
+            let union = Sample::union(&samples);
+            prop_assert_eq!(Sample::union(&vec![union.clone()]), union.clone());
+            prop_assert_eq!(Sample::union(&[samples.clone(), samples].concat()), union);
+        }
+
+        #[test]
+        fn test_union_commutative(samples in prop::collection::vec(sample(), 1..10), rotation in 0..10usize) {
+            let mut rotated = samples.clone();
+            rotated.rotate_left(rotation % samples.len());
+            prop_assert_eq!(Sample::union(&rotated), Sample::union(&samples));
+            rotated.reverse();
+            prop_assert_eq!(Sample::union(&rotated), Sample::union(&samples));
+        }
+
+        #[test]
+        fn test_union_monotone(samples in prop::collection::vec(sample(), 1..10), extra in sample()) {
+            let union = Sample::union(&samples);
+            prop_assert!(samples.iter().all(|sample| union.contains(sample.clone())));
+
+            let mut more = samples.clone();
+            more.push(extra.clone());
+            let bigger_union = Sample::union(&more);
+            prop_assert!(bigger_union.contains(union));
+            prop_assert!(bigger_union.contains(extra));
+        }
+    }
+}
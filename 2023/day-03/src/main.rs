@@ -2,25 +2,73 @@ mod structs;
 use crate::structs::*;
 
 pub fn solve_puzzle1(input: &str) -> i32 {
-    let padded_input: PaddedSchematic = PaddedSchematic::from(input);
-    padded_input
+    let schematic: PaddedSchematic = PaddedSchematic::without_padding(input);
+    schematic
         .part_numbers()
         .into_iter()
         .sum()
 }
 
 pub fn solve_puzzle2(input: &str) -> i32 {
-    let padded_input: PaddedSchematic = PaddedSchematic::from(input);
-    padded_input 
+    let schematic: PaddedSchematic = PaddedSchematic::without_padding(input);
+    schematic
         .find_gears()
         .into_iter()
         .map(|gear| { gear.ratio() })
         .sum()
 }
 
+/// Prints every candidate of a gear query, e.g. for the
+/// arguments `--gears '*' at-least 1 sum`
+fn print_gears(input: &str, args: &[String]) {
+    let [symbol, count, n, aggregation] = args else {
+        panic!("expected arguments: <symbol> <exactly|at-least> <n> <product|sum|list>")
+    };
+    let symbol: char = symbol.chars().next().expect("symbol should not be empty");
+    let n: usize = n.parse().expect("number of neighbours should be parseable as usize");
+    let neighbours = match count.as_str() {
+        "exactly" => NeighbourCount::Exactly(n),
+        "at-least" => NeighbourCount::AtLeast(n),
+        _ => panic!("neighbour count should be either exactly or at-least"),
+    };
+    let query = GearQuery { symbol, neighbours };
+
+    let schematic = PaddedSchematic::without_padding(input);
+    for candidate in schematic.gear_candidates(&query) {
+        match candidate {
+            Ok(gear) => {
+                let value = match aggregation.as_str() {
+                    "product" => gear.ratio().to_string(),
+                    "sum" => gear.sum().to_string(),
+                    "list" => format!("{:?}", gear.part_numbers()),
+                    _ => panic!("aggregation should be one of product, sum or list"),
+                };
+                println!("Gear at {}: {}", gear.pos(), value);
+            }
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--gears") {
+        print_gears(INPUT, &args[i + 1..]);
+        return;
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--render") {
+        let format = match args.get(i + 1).map(|format| format.as_str()) {
+            None | Some("ansi") => RenderFormat::Ansi,
+            Some("plain") => RenderFormat::Plain,
+            Some("html") => RenderFormat::Html,
+            Some(format) => panic!("unknown render format {}", format),
+        };
+        print!("{}", PaddedSchematic::without_padding(INPUT).render(format));
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
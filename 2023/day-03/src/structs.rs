@@ -7,18 +7,87 @@ pub struct BBox {
     pub right: usize,
 }
 
+/// The number of lines and columns of a schematic
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Dimensions {
+    pub height: usize,
+    pub width: usize,
+}
+
 #[derive(Debug)]
 pub struct PaddedSchematic(Vec<String>);
 
+/// A number in the schematic and
+/// the position it spans
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NumberToken {
+    pub bbox: BBox,
+    pub value: i32,
+}
+
+/// A symbol in the schematic and its position
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolToken {
+    pub bbox: BBox,
+    pub character: char,
+}
+
+/// All numbers and symbols of a schematic together with
+/// a bipartite graph of which of them are adjacent
+#[derive(Debug)]
+pub struct SchematicGraph {
+    pub numbers: Vec<NumberToken>,
+    pub symbols: Vec<SymbolToken>,
+    number_neighbours: Vec<Vec<usize>>,
+    symbol_neighbours: Vec<Vec<usize>>,
+}
+
+/// How many numbers a symbol has to be adjacent
+/// to in order to count as a gear
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NeighbourCount {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+/// Describes which symbols count as gears
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GearQuery {
+    pub symbol: char,
+    pub neighbours: NeighbourCount,
+}
+
 #[derive(Debug)]
 pub struct Gear<'a> {
     schematic: &'a PaddedSchematic,
     pos: BBox,
-    part_numbers: [i32; 2],
+    part_numbers: Vec<i32>,
 }
 
-#[derive(Debug)]
-pub struct GearCreationError(BBox);
+/// Output formats of the annotated schematic
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RenderFormat {
+    Ansi,
+    Plain,
+    Html,
+}
+
+/// The role a single character plays in the schematic
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Annotation {
+    Blank,
+    PartNumber,
+    OtherNumber,
+    Symbol,
+    Gear,
+}
+
+/// Why a symbol was rejected as a gear
+#[derive(Debug, PartialEq, Eq)]
+pub enum GearCreationError {
+    WrongSymbol { pos: BBox, found: char },
+    WrongNeighbourCount { pos: BBox, found: usize, expected: NeighbourCount },
+}
 
 impl Display for BBox {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -35,67 +104,56 @@ impl BBox {
         self.right - self.left
     }
 
-    pub fn bbox_to_the_left(&self) -> BBox {
-        BBox::new(self.line, self.left - 1, self.left)
-    }
-
-    pub fn bbox_to_the_right(&self) -> BBox {
-        BBox::new(self.line, self.right, self.right + 1)
-    }
-
-    pub fn bbox_above(&self) -> BBox {
-        BBox::new(self.line - 1, self.left - 1, self.right + 1)
-    }
-
-    pub fn bbox_below(&self) -> BBox {
-        BBox::new(self.line + 1, self.left - 1, self.right + 1)
-    }
-
-    pub fn small_bbox_above(&self) -> BBox {
-        BBox::new(self.line - 1, self.left, self.right)
-    }
-
-    pub fn small_bbox_below(&self) -> BBox {
-        BBox::new(self.line + 1, self.left, self.right)
+    pub fn bbox_to_the_left(&self) -> Option<BBox> {
+        if self.left == 0 {
+            return None
+        }
+        Some(BBox::new(self.line, self.left - 1, self.left))
     }
 
-    pub fn bbox_to_the_top_left(&self) -> BBox {
-        BBox::new(self.line - 1, self.left - 1, self.right - 1)
+    pub fn bbox_to_the_right(&self, dims: &Dimensions) -> Option<BBox> {
+        if self.right >= dims.width {
+            return None
+        }
+        Some(BBox::new(self.line, self.right, self.right + 1))
     }
 
-    pub fn bbox_to_the_top_right(&self) -> BBox {
-        BBox::new(self.line - 1, self.left + 1, self.right + 1)
+    /// The line above including the diagonal corners,
+    /// clipped to the schematic
+    pub fn bbox_above(&self, dims: &Dimensions) -> Option<BBox> {
+        if self.line == 0 {
+            return None
+        }
+        Some(BBox::new(self.line - 1, self.left.saturating_sub(1), (self.right + 1).min(dims.width)))
     }
 
-    pub fn bbox_to_the_bottom_left(&self) -> BBox {
-        BBox::new(self.line + 1, self.left - 1, self.right - 1)
+    /// The line below including the diagonal corners,
+    /// clipped to the schematic
+    pub fn bbox_below(&self, dims: &Dimensions) -> Option<BBox> {
+        if self.line + 1 >= dims.height {
+            return None
+        }
+        Some(BBox::new(self.line + 1, self.left.saturating_sub(1), (self.right + 1).min(dims.width)))
     }
 
-    pub fn bbox_to_the_bottom_right(&self) -> BBox {
-        BBox::new(self.line + 1, self.left + 1, self.right + 1)
+    /// All boxes bordering self, clipped to the schematic
+    pub fn surrounding_bboxes(&self, dims: &Dimensions) -> Vec<BBox> {
+        [
+            self.bbox_to_the_left(),
+            self.bbox_to_the_right(dims),
+            self.bbox_above(dims),
+            self.bbox_below(dims),
+        ]
+            .into_iter()
+            .flatten()
+            .collect()
     }
 
     pub fn is_surrounded_by_symbol(&self, schematic: &PaddedSchematic) -> bool {
-        let mut surrounding_chars: String = String::with_capacity(self.len() * 2 + 6);
-        let mut is_surrounded: bool = false;
-
-        surrounding_chars.extend(schematic.chars_at(&self.bbox_to_the_left()));
-        surrounding_chars.extend(schematic.chars_at(&self.bbox_to_the_right()));
-
-        if self.line > 0 {
-            surrounding_chars.extend(schematic.chars_at(&self.bbox_above()));
-        }
-        if self.line < schematic.0.len() - 1 {
-            surrounding_chars.extend(schematic.chars_at(&self.bbox_below()));
-        }
-
-        for character in surrounding_chars.chars() {
-            if PaddedSchematic::is_symbol(&character) {
-                is_surrounded = true;
-            };
-        }
-
-        is_surrounded
+        self.surrounding_bboxes(&schematic.dimensions())
+            .iter()
+            .flat_map(|bbox| { schematic.chars_at(bbox) })
+            .any(|character| { PaddedSchematic::is_symbol(&character) })
     }
 }
 
@@ -112,6 +170,24 @@ impl From<&str> for PaddedSchematic {
 }
 
 impl PaddedSchematic {
+    /// Reads a schematic as is, without surrounding
+    /// it by a column of dots on either side
+    pub fn without_padding(original_schematic: &str) -> Self {
+        PaddedSchematic(
+            original_schematic
+                .lines()
+                .map(|line| { line.to_string() })
+                .collect()
+        )
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            height: self.0.len(),
+            width: self.0.iter().map(|line| { line.len() }).max().unwrap_or(0),
+        }
+    }
+
     /// Get a Vec of bboxes describing the 
     /// positions of the numbers in
     /// the schematic
@@ -140,6 +216,14 @@ impl PaddedSchematic {
                     }
                 }
             }
+
+            if is_reading {
+                // stop reading a number at the end of the line
+                is_reading = false;
+                bbox.right = line.len();
+                bboxes.push(bbox);
+                bbox = BBox::new(0, 0, 0);
+            }
         }
         bboxes
     }
@@ -167,98 +251,359 @@ impl PaddedSchematic {
         result
     }
 
-    pub fn number_at(&self, bbox: BBox) -> i32 {
-        let mut extended_bbox = bbox;
-        while self.chars_at(&extended_bbox.bbox_to_the_left())[0].is_digit(10) {
-            extended_bbox.left -= 1;
-        }
-        while self.chars_at(&extended_bbox.bbox_to_the_right())[0].is_digit(10) {
-            extended_bbox.right += 1;
-        }
-
-        self 
-            .chars_at(&extended_bbox)
-            .into_iter()
-            .collect::<String>() 
-            .parse::<i32>()
-            .expect("bboxed chars should be castable to i32")
+    /// Indexes all numbers and symbols in one pass
+    pub fn graph(&self) -> SchematicGraph {
+        SchematicGraph::from(self)
     }
 
     pub fn part_numbers(&self) -> Vec<i32> {
-        self 
-            .number_bboxes()
+        self.graph().part_numbers()
+    }
+
+    /// Finds the gears of part two
+    pub fn find_gears(&self) -> Vec<Gear<'_>> {
+        self.find_gears_with(&GearQuery::default())
+    }
+
+    /// Finds all gears matching the query
+    pub fn find_gears_with(&self, query: &GearQuery) -> Vec<Gear<'_>> {
+        let graph = self.graph();
+        graph
+            .symbols_with_neighbours(query.symbol, query.neighbours)
             .into_iter()
-            .filter(|bbox| { bbox.is_surrounded_by_symbol(&self) })
-            .map(|bbox| { self.number_at(bbox) })
+            .filter_map(|symbol| { Gear::new(self, &graph, symbol, query).ok() })
             .collect()
     }
 
-    pub fn find_gears(&self) -> Vec<Gear> {
-        let mut gears: Vec<Gear> = vec![];
+    /// Tries to create a gear from every symbol with the
+    /// queried character, keeping the reasons for rejections
+    pub fn gear_candidates(&self, query: &GearQuery) -> Vec<Result<Gear<'_>, GearCreationError>> {
+        let graph = self.graph();
+        (0..graph.symbols.len())
+            .filter(|&symbol| { graph.symbols[symbol].character == query.symbol })
+            .map(|symbol| { Gear::new(self, &graph, symbol, query) })
+            .collect()
+    }
+}
 
-        for (i, line) in self.0.iter().enumerate() {
-            for (j, character) in line.chars().enumerate() {
-                if character == '*' {
-                    let pos = BBox::new(i, j, j + 1);
-                    match Gear::new(self, pos) {
-                        Ok(gear) => gears.push(gear),
-                        Err(_) => (),
+impl PaddedSchematic {
+    /// Annotates every character of the schematic. Numbers are
+    /// classified by is_surrounded_by_symbol and gears by find_gears
+    pub fn annotations(&self) -> Vec<Vec<Annotation>> {
+        let mut annotations: Vec<Vec<Annotation>> = self.0
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .map(|character| {
+                        if PaddedSchematic::is_symbol(&character) {
+                            Annotation::Symbol
+                        } else {
+                            Annotation::Blank
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for bbox in self.number_bboxes() {
+            let annotation = if bbox.is_surrounded_by_symbol(self) {
+                Annotation::PartNumber
+            } else {
+                Annotation::OtherNumber
+            };
+            annotations[bbox.line][bbox.left..bbox.right].fill(annotation);
+        }
+
+        for gear in self.find_gears() {
+            let pos = gear.pos();
+            annotations[pos.line][pos.left..pos.right].fill(Annotation::Gear);
+        }
+
+        annotations
+    }
+
+    /// Renders the schematic with part numbers, other
+    /// numbers, symbols and gears highlighted
+    pub fn render(&self, format: RenderFormat) -> String {
+        let annotations = self.annotations();
+        let mut output = String::new();
+
+        if format == RenderFormat::Html {
+            output.push_str(concat!(
+                "<style>\n",
+                ".part { color: green; }\n",
+                ".other { color: red; }\n",
+                ".symbol { color: orange; }\n",
+                ".gear { color: magenta; font-weight: bold; }\n",
+                "</style>\n",
+                "<pre>\n",
+            ));
+        }
+
+        for (line, line_annotations) in self.0.iter().zip(&annotations) {
+            match format {
+                RenderFormat::Plain => {
+                    // the annotations go on a separate line below
+                    // so that the columns stay aligned
+                    let markers: String = line_annotations
+                        .iter()
+                        .map(|annotation| { annotation.marker() })
+                        .collect();
+                    output.push_str(line);
+                    output.push('\n');
+                    output.push_str(markers.trim_end());
+                    output.push('\n');
+                }
+                RenderFormat::Ansi | RenderFormat::Html => {
+                    let characters: Vec<char> = line.chars().collect();
+                    let mut start = 0;
+                    while start < characters.len() {
+                        let annotation = line_annotations[start];
+                        let mut end = start + 1;
+                        while end < characters.len() && line_annotations[end] == annotation {
+                            end += 1;
+                        }
+                        let run: String = characters[start..end].iter().collect();
+                        output.push_str(&annotation.highlight(&run, format));
+                        start = end;
                     }
+                    output.push('\n');
                 }
             }
         }
 
-        gears
+        if format == RenderFormat::Html {
+            output.push_str("</pre>\n");
+        }
+        output
     }
 }
 
-impl<'a> Gear<'a> {
-    pub fn new(schematic: &'a PaddedSchematic, pos: BBox) -> Result<Gear<'a>, GearCreationError> {
-        let mut part_numbers: Vec<i32> = vec![];
+impl Annotation {
+    /// Character marking the annotation in plain text
+    pub fn marker(&self) -> char {
+        match self {
+            Annotation::Blank => ' ',
+            Annotation::PartNumber => 'P',
+            Annotation::OtherNumber => 'n',
+            Annotation::Symbol => 'S',
+            Annotation::Gear => 'G',
+        }
+    }
 
-        fn try_adding_part_numbers(pos: BBox, part_numbers: &mut Vec<i32>, schematic: &PaddedSchematic) -> bool {
-            if schematic.chars_at(&pos)[0].is_digit(10) {
-                part_numbers.push(schematic.number_at(pos));
-                true
-            } else {
-                false
+    /// Wraps text in the highlighting of the given format
+    pub fn highlight(&self, text: &str, format: RenderFormat) -> String {
+        match format {
+            RenderFormat::Plain => text.to_string(),
+            RenderFormat::Ansi => {
+                let code = match self {
+                    Annotation::Blank => return text.to_string(),
+                    Annotation::PartNumber => "32",
+                    Annotation::OtherNumber => "31",
+                    Annotation::Symbol => "33",
+                    Annotation::Gear => "1;35",
+                };
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            }
+            RenderFormat::Html => {
+                let escaped = text
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;");
+                let class = match self {
+                    Annotation::Blank => return escaped,
+                    Annotation::PartNumber => "part",
+                    Annotation::OtherNumber => "other",
+                    Annotation::Symbol => "symbol",
+                    Annotation::Gear => "gear",
+                };
+                format!("<span class=\"{}\">{}</span>", class, escaped)
             }
         }
+    }
+}
+
+impl From<&PaddedSchematic> for SchematicGraph {
+    fn from(schematic: &PaddedSchematic) -> Self {
+        let dims = schematic.dimensions();
+        let mut numbers: Vec<NumberToken> = vec![];
+        let mut symbols: Vec<SymbolToken> = vec![];
+        // index of the number covering each character, if any
+        let mut number_at: Vec<Vec<Option<usize>>> = vec![vec![None; dims.width]; dims.height];
+
+        for (i, line) in schematic.0.iter().enumerate() {
+            let mut number: Option<NumberToken> = None;
+
+            // a trailing dot finishes numbers at the end of the line
+            for (j, character) in line.chars().chain(std::iter::once('.')).enumerate() {
+                if let Some(digit) = character.to_digit(10) {
+                    let token = number.get_or_insert(NumberToken { bbox: BBox::new(i, j, j), value: 0 });
+                    token.bbox.right = j + 1;
+                    token.value = token.value * 10 + digit as i32;
+                    number_at[i][j] = Some(numbers.len());
+                    continue
+                }
+
+                if let Some(token) = number.take() {
+                    numbers.push(token);
+                }
+                if PaddedSchematic::is_symbol(&character) {
+                    symbols.push(SymbolToken { bbox: BBox::new(i, j, j + 1), character });
+                }
+            }
+        }
+
+        let mut number_neighbours: Vec<Vec<usize>> = vec![vec![]; numbers.len()];
+        let mut symbol_neighbours: Vec<Vec<usize>> = Vec::with_capacity(symbols.len());
+        for (s, symbol) in symbols.iter().enumerate() {
+            let mut neighbours: Vec<usize> = symbol.bbox
+                .surrounding_bboxes(&dims)
+                .iter()
+                .flat_map(|bbox| { number_at[bbox.line][bbox.left..bbox.right].iter() })
+                .flatten()
+                .copied()
+                .collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+
+            for &n in &neighbours {
+                number_neighbours[n].push(s);
+            }
+            symbol_neighbours.push(neighbours);
+        }
+
+        SchematicGraph { numbers, symbols, number_neighbours, symbol_neighbours }
+    }
+}
+
+impl SchematicGraph {
+    /// Indices of all symbols adjacent to the given number
+    pub fn symbols_next_to(&self, number: usize) -> &[usize] {
+        &self.number_neighbours[number]
+    }
 
-        // add part numbers to the sides
-        try_adding_part_numbers(pos.bbox_to_the_left(), &mut part_numbers, schematic);
-        try_adding_part_numbers(pos.bbox_to_the_right(), &mut part_numbers, schematic);
+    /// Indices of all numbers adjacent to the given symbol
+    pub fn numbers_next_to(&self, symbol: usize) -> &[usize] {
+        &self.symbol_neighbours[symbol]
+    }
+
+    /// Values of all numbers adjacent to at
+    /// least one symbol, in reading order
+    pub fn part_numbers(&self) -> Vec<i32> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| { !self.symbols_next_to(*n).is_empty() })
+            .map(|(_, number)| { number.value })
+            .collect()
+    }
+
+    /// Indices of all symbols of the given character that
+    /// are adjacent to an accepted number of numbers
+    pub fn symbols_with_neighbours(&self, character: char, neighbours: NeighbourCount) -> Vec<usize> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(|(s, symbol)| {
+                symbol.character == character && neighbours.accepts(self.numbers_next_to(*s).len())
+            })
+            .map(|(s, _)| { s })
+            .collect()
+    }
+}
+
+impl NeighbourCount {
+    pub fn accepts(&self, n: usize) -> bool {
+        match self {
+            NeighbourCount::Exactly(expected) => n == *expected,
+            NeighbourCount::AtLeast(expected) => n >= *expected,
+        }
+    }
+}
 
-        if !try_adding_part_numbers(pos.small_bbox_above(), &mut part_numbers, schematic) {
-            // add part numbers in the upper corners
-            try_adding_part_numbers(pos.bbox_to_the_top_left(), &mut part_numbers, schematic);
-            try_adding_part_numbers(pos.bbox_to_the_top_right(), &mut part_numbers, schematic);
+impl Display for NeighbourCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NeighbourCount::Exactly(n) => write!(f, "exactly {}", n),
+            NeighbourCount::AtLeast(n) => write!(f, "at least {}", n),
         }
+    }
+}
+
+impl Default for GearQuery {
+    /// The gears of part two: stars
+    /// next to exactly two numbers
+    fn default() -> Self {
+        GearQuery { symbol: '*', neighbours: NeighbourCount::Exactly(2) }
+    }
+}
 
-        if !try_adding_part_numbers(pos.small_bbox_below(), &mut part_numbers, schematic) {
-            // add part numbers in the bottom corners
-            try_adding_part_numbers(pos.bbox_to_the_bottom_left(), &mut part_numbers, schematic);
-            try_adding_part_numbers(pos.bbox_to_the_bottom_right(), &mut part_numbers, schematic);
+impl<'a> Gear<'a> {
+    pub fn new(
+        schematic: &'a PaddedSchematic,
+        graph: &SchematicGraph,
+        symbol: usize,
+        query: &GearQuery,
+    ) -> Result<Gear<'a>, GearCreationError> {
+        let pos = graph.symbols[symbol].bbox.clone();
+        let found = graph.symbols[symbol].character;
+        if found != query.symbol {
+            return Err(GearCreationError::WrongSymbol { pos, found })
         }
 
-        if part_numbers.len() == 2 {
-            let part_numbers: [i32; 2] = part_numbers.try_into()
-                .expect("part numbers vector should have 2 elements");
-            let gear: Gear<'a> = Gear { schematic, pos, part_numbers };
-            Ok(gear)
-        } else {
-            Err(GearCreationError(pos))
+        let neighbours = graph.numbers_next_to(symbol);
+        if !query.neighbours.accepts(neighbours.len()) {
+            return Err(GearCreationError::WrongNeighbourCount {
+                pos,
+                found: neighbours.len(),
+                expected: query.neighbours,
+            })
         }
+
+        let part_numbers: Vec<i32> = neighbours
+            .iter()
+            .map(|&n| { graph.numbers[n].value })
+            .collect();
+        Ok(Gear { schematic, pos, part_numbers })
     }
 
+    pub fn pos(&self) -> &BBox {
+        &self.pos
+    }
+
+    /// The adjacent numbers in reading order
+    pub fn part_numbers(&self) -> &[i32] {
+        &self.part_numbers
+    }
+
+    /// Combines the adjacent numbers with an arbitrary function
+    pub fn aggregate<T>(&self, f: impl Fn(&[i32]) -> T) -> T {
+        f(&self.part_numbers)
+    }
+
+    /// The product of all adjacent numbers
     pub fn ratio(&self) -> i32 {
-        self.part_numbers.into_iter().product()
+        self.aggregate(|part_numbers| { part_numbers.iter().product() })
+    }
+
+    /// The sum of all adjacent numbers
+    pub fn sum(&self) -> i32 {
+        self.aggregate(|part_numbers| { part_numbers.iter().sum() })
     }
 }
 
 impl fmt::Display for GearCreationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "No gear at position {}", self.0)
+        match self {
+            GearCreationError::WrongSymbol { pos, found } => {
+                write!(f, "No gear at position {}, found symbol '{}'", pos, found)
+            }
+            GearCreationError::WrongNeighbourCount { pos, found, expected } => write!(
+                f,
+                "No gear at position {}, found {} adjacent numbers instead of {}",
+                pos, found, expected
+            ),
+        }
     }
 }
 
@@ -370,4 +715,164 @@ mod tests {
         let result = schematic.part_numbers();
         assert_eq!(result, output);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bbox_neighbours_at_edges() {
+        let dims = Dimensions { height: 3, width: 5 };
+        let top_left = BBox::new(0, 0, 2);
+        assert_eq!(top_left.bbox_to_the_left(), None);
+        assert_eq!(top_left.bbox_above(&dims), None);
+        assert_eq!(top_left.bbox_below(&dims), Some(BBox::new(1, 0, 3)));
+
+        let bottom_right = BBox::new(2, 3, 5);
+        assert_eq!(bottom_right.bbox_to_the_right(&dims), None);
+        assert_eq!(bottom_right.bbox_below(&dims), None);
+        assert_eq!(bottom_right.bbox_above(&dims), Some(BBox::new(1, 2, 5)));
+    }
+
+    #[test]
+    fn test_unpadded_schematic_part_numbers() {
+        let schematic = PaddedSchematic::without_padding("467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+"
+        );
+        let output = vec![467, 35, 633, 617, 592, 755, 664, 598];
+        let result = schematic.part_numbers();
+        assert_eq!(result, output);
+    }
+
+    #[test]
+    fn test_unpadded_schematic_gears_on_edges() {
+        let inputs = vec!["12*34\n.....\n", ".....\n56*78\n", "*5\n6.\n"];
+        let outputs = vec![vec![408], vec![4368], vec![30]];
+        for (input, output) in inputs.into_iter().zip(outputs) {
+            let schematic = PaddedSchematic::without_padding(input);
+            let result: Vec<i32> = schematic
+                .find_gears()
+                .iter()
+                .map(|gear| { gear.ratio() })
+                .collect();
+            assert_eq!(result, output);
+        }
+    }
+
+    #[test]
+    fn test_schematic_graph() {
+        let schematic = PaddedSchematic::without_padding("467..114..
+...*......
+..35..633.
+......#...
+617*......
+");
+        let graph = schematic.graph();
+        let values: Vec<i32> = graph.numbers.iter().map(|number| { number.value }).collect();
+        let characters: Vec<char> = graph.symbols.iter().map(|symbol| { symbol.character }).collect();
+        assert_eq!(values, vec![467, 114, 35, 633, 617]);
+        assert_eq!(characters, vec!['*', '#', '*']);
+        assert_eq!(graph.numbers[2].bbox, BBox::new(2, 2, 4));
+
+        assert_eq!(graph.numbers_next_to(0), &[0, 2]);
+        assert_eq!(graph.numbers_next_to(1), &[3]);
+        assert_eq!(graph.numbers_next_to(2), &[4]);
+        assert_eq!(graph.symbols_next_to(1), &[] as &[usize]);
+        assert_eq!(graph.symbols_next_to(2), &[0]);
+
+        assert_eq!(graph.part_numbers(), vec![467, 35, 633, 617]);
+        assert_eq!(graph.symbols_with_neighbours('*', NeighbourCount::Exactly(2)), vec![0]);
+        assert_eq!(graph.symbols_with_neighbours('*', NeighbourCount::Exactly(1)), vec![2]);
+    }
+
+    #[test]
+    fn test_gear_queries() {
+        let schematic = PaddedSchematic::without_padding("467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+"
+        );
+        let query = GearQuery { symbol: '*', neighbours: NeighbourCount::AtLeast(1) };
+        let gears = schematic.find_gears_with(&query);
+        let sums: Vec<i32> = gears.iter().map(|gear| { gear.sum() }).collect();
+        assert_eq!(sums, vec![502, 617, 1353]);
+        assert_eq!(gears[2].part_numbers(), &[755, 598]);
+
+        let query = GearQuery { symbol: '+', neighbours: NeighbourCount::Exactly(1) };
+        let maxima: Vec<i32> = schematic
+            .find_gears_with(&query)
+            .iter()
+            .map(|gear| { gear.aggregate(|part_numbers| { *part_numbers.iter().max().unwrap() }) })
+            .collect();
+        assert_eq!(maxima, vec![592]);
+    }
+
+    #[test]
+    fn test_gear_candidates_report_rejections() {
+        let schematic = PaddedSchematic::without_padding("467..114..
+...*......
+..35..633.
+......#...
+617*......
+");
+        let candidates = schematic.gear_candidates(&GearQuery::default());
+        assert!(candidates[0].is_ok());
+        assert_eq!(
+            candidates[1].as_ref().unwrap_err(),
+            &GearCreationError::WrongNeighbourCount {
+                pos: BBox::new(4, 3, 4),
+                found: 1,
+                expected: NeighbourCount::Exactly(2),
+            }
+        );
+
+        let graph = schematic.graph();
+        let result = Gear::new(&schematic, &graph, 1, &GearQuery::default()).unwrap_err();
+        assert_eq!(result, GearCreationError::WrongSymbol { pos: BBox::new(3, 6, 7), found: '#' });
+    }
+
+    #[test]
+    fn test_schematic_render_plain() {
+        let schematic = PaddedSchematic::without_padding("467..114..
+...*......
+..35..633.
+......#...
+617*......
+");
+        let output = "467..114..
+PPP  nnn
+...*......
+   G
+..35..633.
+  PP  PPP
+......#...
+      S
+617*......
+PPPS
+";
+        assert_eq!(schematic.render(RenderFormat::Plain), output);
+    }
+
+    #[test]
+    fn test_schematic_render_highlighted() {
+        let schematic = PaddedSchematic::without_padding("1..<\n..*2\n");
+        let ansi = "\x1b[31m1\x1b[0m..\x1b[33m<\x1b[0m\n..\x1b[33m*\x1b[0m\x1b[32m2\x1b[0m\n";
+        assert_eq!(schematic.render(RenderFormat::Ansi), ansi);
+
+        let html = schematic.render(RenderFormat::Html);
+        assert!(html.contains("<span class=\"symbol\">&lt;</span>"));
+        assert!(html.contains("..<span class=\"symbol\">*</span><span class=\"part\">2</span>"));
+    }
+}
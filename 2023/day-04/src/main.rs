@@ -16,13 +16,26 @@ pub fn solve_puzzle1(input: &str) -> i32 {
 
 pub fn solve_puzzle2(input: &str) -> i32 {
     let mut card_pile = CardPile::from(input);
-    card_pile.win_more_cards();
+    card_pile.win_more_cards().unwrap_or_else(|err| panic!("{}", err));
     card_pile.count_cards() as i32
 }
 
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--trace") {
+        let mut card_pile = CardPile::from(INPUT).with_trace();
+        card_pile.win_more_cards().unwrap_or_else(|err| panic!("{}", err));
+        let trace = match args.get(i + 1).map(|format| format.as_str()) {
+            None | Some("table") => card_pile.trace_table(),
+            Some("dot") => card_pile.trace_dot(),
+            Some(format) => panic!("unknown trace format {}", format),
+        };
+        print!("{}", trace.expect("trace should have been recorded"));
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
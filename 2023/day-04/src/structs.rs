@@ -1,3 +1,6 @@
+use std::collections::HashSet;
+use std::fmt;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Card {
     pub id: usize,
@@ -8,6 +11,32 @@ pub struct Card {
 #[derive(Debug, Eq, PartialEq)]
 pub struct CardPile {
     vec: Vec<(Card, u32)>,
+    trace: Option<Vec<CopyEvent>>,
+}
+
+/// Copies of one card won by all copies of another card
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CopyEvent {
+    pub from_id: usize,
+    pub to_id: usize,
+    pub copies: u32,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum WinCardsError {
+    /// Two cards in the pile share an id
+    DuplicateId(usize),
+    /// The copies of the card with this id do not fit into a u32
+    TooManyCopies(usize),
+}
+
+impl fmt::Display for WinCardsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WinCardsError::DuplicateId(id) => write!(f, "Card {} appears more than once.", id),
+            WinCardsError::TooManyCopies(id) => write!(f, "Card {} has too many copies to count.", id),
+        }
+    }
 }
 
 impl From<&str> for Card {
@@ -65,10 +94,10 @@ impl Card {
     }
 
     pub fn count_wins(&self) -> usize {
+        let winning_numbers: HashSet<&i32> = self.winning_numbers.iter().collect();
         self.own_numbers
             .iter()
-            .map(|own_number| self.winning_numbers.as_slice().contains(own_number))
-            .filter(|boolean| *boolean)
+            .filter(|own_number| winning_numbers.contains(own_number))
             .count()
     }
 }
@@ -77,11 +106,23 @@ impl From<&str> for CardPile {
     fn from(input: &str) -> CardPile {
         CardPile {
             vec: input.lines().map(|line| (Card::from(line), 1)).collect(),
+            trace: None,
         }
     }
 }
 
 impl CardPile {
+    /// Makes win_more_cards record which card won
+    /// how many copies of which other card
+    pub fn with_trace(mut self) -> CardPile {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn trace(&self) -> Option<&[CopyEvent]> {
+        self.trace.as_deref()
+    }
+
     pub fn count_points(&self) -> u32 {
         self.vec
             .iter()
@@ -89,24 +130,48 @@ impl CardPile {
             .sum()
     }
 
-    pub fn copy_card(&mut self, game_id: usize, n_times: u32) {
-        let card_to_copy: &mut (Card, u32) = self
-            .vec
-            .get_mut(game_id - 1)
-            .expect("given id should be contained in card pile");
-        card_to_copy.1 += n_times;
-    }
+    /// Lets every card win copies of the cards with the next
+    /// ids. Ids missing from the pile or past its end win nothing.
+    /// Runs in O(n log n) for n cards, however far apart their ids are
+    pub fn win_more_cards(&mut self) -> Result<(), WinCardsError> {
+        let mut order: Vec<usize> = (0..self.vec.len()).collect();
+        order.sort_unstable_by_key(|&i| self.vec[i].0.id);
+        let ids: Vec<usize> = order.iter().map(|&i| self.vec[i].0.id).collect();
+        if let Some(pair) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(WinCardsError::DuplicateId(pair[0]));
+        }
+
+        // difference array of the copies won by each card in id order
+        let mut won_copies: Vec<i64> = vec![0; ids.len() + 1];
+        let mut pending: i64 = 0;
+        for (position, &i) in order.iter().enumerate() {
+            pending += won_copies[position];
+            let card = &mut self.vec[i];
+            card.1 = u32::try_from(pending)
+                .ok()
+                .and_then(|won| card.1.checked_add(won))
+                .ok_or(WinCardsError::TooManyCopies(card.0.id))?;
 
-    pub fn win_more_cards(&mut self) {
-        for i in 0..self.vec.len() - 1 {
-            let card = self.vec.get(i).expect("vector should be indexable by i");
-            let wins = card.0.count_wins();
-            let card_id = card.0.id;
-            let n_copies = card.1;
-            for j in 1..wins + 1 {
-                self.copy_card(card_id + j, n_copies);
+            // the cards with the next ids directly follow this one in id order
+            let last_id = card.0.id.saturating_add(card.0.count_wins());
+            let end = ids.partition_point(|&id| id <= last_id);
+            if end > position + 1 {
+                let n_copies = i64::from(card.1);
+                won_copies[position + 1] += n_copies;
+                won_copies[end] -= n_copies;
+
+                if let Some(trace) = self.trace.as_mut() {
+                    for &to_id in &ids[position + 1..end] {
+                        trace.push(CopyEvent {
+                            from_id: card.0.id,
+                            to_id,
+                            copies: card.1,
+                        });
+                    }
+                }
             }
         }
+        Ok(())
     }
 
     pub fn count_copies(&self) -> Vec<u32> {
@@ -116,11 +181,47 @@ impl CardPile {
     pub fn count_cards(&self) -> u32 {
         self.count_copies().iter().sum()
     }
+
+    /// Writes the recorded trace as a plain text table
+    pub fn trace_table(&self) -> Option<String> {
+        let trace = self.trace()?;
+        let mut table = format!("{:>6} | {:>6} | {:>8}\n", "from", "to", "copies");
+        table.push_str(&format!("{:-<6}-+-{:-<6}-+-{:-<8}\n", "", "", ""));
+        for event in trace {
+            table.push_str(&format!(
+                "{:>6} | {:>6} | {:>8}\n",
+                event.from_id, event.to_id, event.copies
+            ));
+        }
+        Some(table)
+    }
+
+    /// Writes the recorded trace as a Graphviz digraph with
+    /// one node per card and one edge per copy event
+    pub fn trace_dot(&self) -> Option<String> {
+        let trace = self.trace()?;
+        let mut dot = String::from("digraph cascade {\n");
+        for (card, copies) in &self.vec {
+            dot.push_str(&format!(
+                "    card{} [label=\"Card {}\\n{} total\"];\n",
+                card.id, card.id, copies
+            ));
+        }
+        for event in trace {
+            dot.push_str(&format!(
+                "    card{} -> card{} [label=\"{}\"];\n",
+                event.from_id, event.to_id, event.copies
+            ));
+        }
+        dot.push_str("}\n");
+        Some(dot)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn card_pile_count_copies() {
@@ -134,8 +235,135 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         let output = vec![1, 2, 4, 8, 14, 1];
 
         let mut card_pile = CardPile::from(EXAMPLE_INPUT);
-        card_pile.win_more_cards();
+        card_pile.win_more_cards().unwrap();
         let result = card_pile.count_copies();
         assert_eq!(result, output);
     }
+
+    #[test]
+    fn card_pile_wins_past_the_end() {
+        const INPUT: &str = "Card 1: 1 2 | 1 3
+Card 2: 4 5 6 | 4 5 6
+";
+        let mut card_pile = CardPile::from(INPUT);
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_copies(), vec![1, 2]);
+    }
+
+    #[test]
+    fn card_pile_with_gaps_in_ids() {
+        const INPUT: &str = "Card 1: 1 2 | 1 2
+Card 3: 7 | 8
+Card 4: 9 | 9
+";
+        let mut card_pile = CardPile::from(INPUT);
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_copies(), vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn card_pile_out_of_order() {
+        const INPUT: &str = "Card 3: 7 | 8
+Card 2: 5 | 5
+Card 1: 1 2 | 1 2
+";
+        let mut card_pile = CardPile::from(INPUT);
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_copies(), vec![4, 2, 1]);
+    }
+
+    #[test]
+    fn card_pile_empty_and_single() {
+        let mut card_pile = CardPile::from("");
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_cards(), 0);
+
+        let mut card_pile = CardPile::from("Card 7: 1 | 1");
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_copies(), vec![1]);
+    }
+
+    #[test]
+    fn card_pile_with_far_apart_ids() {
+        const INPUT: &str = "Card 1: 1 | 1
+Card 1000000000: 2 | 2
+Card 2: 3 | 4
+";
+        let mut card_pile = CardPile::from(INPUT);
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.count_copies(), vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn card_pile_errors() {
+        let mut card_pile = CardPile::from("Card 1: 1 | 1\nCard 2: 2 | 3\nCard 1: 4 | 5\n");
+        assert_eq!(card_pile.win_more_cards(), Err(WinCardsError::DuplicateId(1)));
+
+        let mut card_pile = CardPile::from("Card 1: 1 | 1\nCard 2: 2 | 3\n");
+        card_pile.vec[0].1 = u32::MAX;
+        assert_eq!(card_pile.win_more_cards(), Err(WinCardsError::TooManyCopies(2)));
+    }
+
+    #[test]
+    fn card_pile_trace() {
+        const INPUT: &str = "Card 1: 1 2 | 1 2
+Card 3: 7 | 7
+Card 4: 9 | 8
+";
+        let mut card_pile = CardPile::from(INPUT);
+        card_pile.win_more_cards().unwrap();
+        assert_eq!(card_pile.trace(), None);
+
+        let mut card_pile = CardPile::from(INPUT).with_trace();
+        card_pile.win_more_cards().unwrap();
+        let output = vec![
+            CopyEvent {
+                from_id: 1,
+                to_id: 3,
+                copies: 1,
+            },
+            CopyEvent {
+                from_id: 3,
+                to_id: 4,
+                copies: 2,
+            },
+        ];
+        assert_eq!(card_pile.trace(), Some(output.as_slice()));
+        assert_eq!(card_pile.count_copies(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn card_pile_trace_exports() {
+        let mut card_pile = CardPile::from("Card 1: 5 | 5\nCard 2: 6 | 7\n").with_trace();
+        card_pile.win_more_cards().unwrap();
+
+        let table = "  from |     to |   copies
+-------+--------+---------
+     1 |      2 |        1
+";
+        assert_eq!(card_pile.trace_table().unwrap(), table);
+
+        let dot = "digraph cascade {
+    card1 [label=\"Card 1\\n1 total\"];
+    card2 [label=\"Card 2\\n2 total\"];
+    card1 -> card2 [label=\"1\"];
+}
+";
+        assert_eq!(card_pile.trace_dot().unwrap(), dot);
+    }
+
+    proptest! {
+        #[test]
+        fn test_count_points_doubles_per_win(
+            own in prop::collection::hash_set(1..100i32, 0..25),
+            winning in prop::collection::hash_set(1..100i32, 0..10),
+        ) {
+            let card = Card { id: 1, own_numbers: own.into_iter().collect(), winning_numbers: winning.into_iter().collect() };
+            let wins = card.own_numbers.iter().filter(|number| card.winning_numbers.contains(number)).count();
+
+            prop_assert_eq!(card.count_wins(), wins);
+            let expected = if wins == 0 { 0 } else { 2_u32.pow(wins as u32 - 1) };
+            prop_assert_eq!(card.count_points(), expected);
+        }
+    }
 }
@@ -8,24 +8,37 @@ pub fn solve_puzzle1(input: &str) -> i32 {
     almanac.nearest_seed_location() as i32
 }
 
-pub fn solve_puzzle2(input: &str) -> i32 {
+/// Reference for part two that follows every single seed of the ranges
+pub fn solve_puzzle2_brute_force(input: &str) -> i32 {
     let almanac = Almanac::from_ranges(input);
     almanac.nearest_seed_location() as i32
 }
 
+pub fn solve_puzzle2(input: &str) -> i32 {
+    let almanac = Almanac::from(input);
+    almanac
+        .nearest_seed_range_location()
+        .unwrap_or_else(|err| panic!("{}", err)) as i32
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
-    let solution2 = solve_puzzle2(INPUT);
+    let solution2 = if std::env::args().any(|arg| arg == "--brute-force") {
+        solve_puzzle2_brute_force(INPUT)
+    } else {
+        solve_puzzle2(INPUT)
+    };
     println!("Solution to puzzle two: {}", solution2);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::{almanac, Differential};
 
     #[test]
     fn test_example_input1() {
@@ -40,4 +53,10 @@ mod tests {
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        let differential = Differential::new(1_000, 10);
+        differential.assert_agree("2023 day 5 part 2", almanac, solve_puzzle2_brute_force, solve_puzzle2);
+    }
 }
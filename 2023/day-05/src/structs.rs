@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use std::fmt;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct MapRange {
@@ -18,6 +19,24 @@ pub struct Almanac {
     maps: Vec<Map>,
 }
 
+/// Why the seeds cannot be read as ranges
+#[derive(Debug, Eq, PartialEq)]
+pub enum SeedRangeError {
+    /// The last seed has no length to go with it
+    UnpairedSeed(u64),
+    /// The ranges contain no seeds at all
+    NoSeeds,
+}
+
+impl fmt::Display for SeedRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedRangeError::UnpairedSeed(seed) => write!(f, "Seed {} has no range length.", seed),
+            SeedRangeError::NoSeeds => write!(f, "The seed ranges are empty."),
+        }
+    }
+}
+
 impl From<&str> for Almanac {
     fn from(value: &str) -> Self {
         let mut split = if value.contains("\r\n") {
@@ -48,6 +67,25 @@ impl Almanac {
             .unwrap()
     }
 
+    /// Reads the seeds as pairs of start and length and follows whole
+    /// ranges through the maps instead of every single seed
+    pub fn nearest_seed_range_location(&self) -> Result<u64, SeedRangeError> {
+        let chunks = self.seeds.chunks_exact(2);
+        if let [seed] = chunks.remainder() {
+            return Err(SeedRangeError::UnpairedSeed(*seed));
+        }
+        let mut ranges: Vec<[u64; 2]> = chunks.map(|chunk| [chunk[0], chunk[1]]).collect();
+        for map in &self.maps {
+            ranges = map.convert_ranges(&ranges);
+        }
+        ranges
+            .into_iter()
+            .filter(|range| range[1] > 0)
+            .map(|range| range[0])
+            .min()
+            .ok_or(SeedRangeError::NoSeeds)
+    }
+
     pub fn seed_location(&self, seed_id: u64) -> u64 {
         let mut map_target = seed_id;
         for map in &self.maps {
@@ -105,12 +143,49 @@ impl From<&str> for Map {
 impl Map {
     pub fn convert(&self, id: u64) -> u64 {
         for range in &self.vec {
-            if (range.source_start..=range.source_start + range.len as u64).contains(&(id)) {
-                return id + (range.target_start - range.source_start);
+            if (range.source_start..range.source_start + range.len as u64).contains(&(id)) {
+                return id - range.source_start + range.target_start;
             }
         }
         id
     }
+
+    /// Converts ranges given as [start, length], splitting them
+    /// wherever they only partly overlap a map range
+    pub fn convert_ranges(&self, ranges: &[[u64; 2]]) -> Vec<[u64; 2]> {
+        let mut converted: Vec<[u64; 2]> = vec![];
+        // [start, end) of the parts not converted by any map range yet
+        let mut pending: Vec<[u64; 2]> = ranges
+            .iter()
+            .map(|range| [range[0], range[0] + range[1]])
+            .collect();
+
+        for map_range in &self.vec {
+            let source_end = map_range.source_start + map_range.len as u64;
+            let mut unmatched = vec![];
+            for [start, end] in pending {
+                let overlap = [start.max(map_range.source_start), end.min(source_end)];
+                if overlap[0] >= overlap[1] {
+                    unmatched.push([start, end]);
+                    continue;
+                }
+                converted.push([
+                    overlap[0] - map_range.source_start + map_range.target_start,
+                    overlap[1] - overlap[0],
+                ]);
+                if start < overlap[0] {
+                    unmatched.push([start, overlap[0]]);
+                }
+                if overlap[1] < end {
+                    unmatched.push([overlap[1], end]);
+                }
+            }
+            pending = unmatched;
+        }
+
+        converted.extend(pending.into_iter().map(|[start, end]| [start, end - start]));
+        converted
+    }
 }
 
 impl From<&str> for MapRange {
@@ -131,6 +206,7 @@ impl From<&str> for MapRange {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_map_convert() {
@@ -155,6 +231,51 @@ mod tests {
         assert_eq!(results, outputs);
     }
 
+    #[test]
+    fn test_map_convert_range_bounds() {
+        // the source range covers 98 and 99, so 100 is past its end and stays put
+        let map = Map { vec: vec![MapRange { source_start: 98, target_start: 50, len: 2 }] };
+        assert_eq!(map.convert(97), 97);
+        assert_eq!(map.convert(98), 50);
+        assert_eq!(map.convert(99), 51);
+        assert_eq!(map.convert(100), 100);
+    }
+
+    #[test]
+    fn test_map_convert_to_lower_target() {
+        // moving ids down used to compute target_start - source_start, which underflows
+        let map = Map { vec: vec![MapRange { source_start: 1_000, target_start: 3, len: 10 }] };
+        assert_eq!(map.convert(1_000), 3);
+        assert_eq!(map.convert(1_009), 12);
+    }
+
+    #[test]
+    fn test_map_convert_ranges() {
+        let map = Map {
+            vec: vec![
+                MapRange { source_start: 98, target_start: 50, len: 2 },
+                MapRange { source_start: 50, target_start: 52, len: 48 },
+            ],
+        };
+
+        let mut result = map.convert_ranges(&[[45, 10], [97, 5]]);
+        result.sort_unstable();
+        assert_eq!(result, vec![[45, 5], [50, 2], [52, 5], [99, 1], [100, 2]]);
+        assert_eq!(map.convert(100), 100);
+    }
+
+    #[test]
+    fn test_nearest_seed_range_location_errors() {
+        let almanac = Almanac::from("seeds: 79 14 55\n\nmap:\n50 98 2");
+        assert_eq!(almanac.nearest_seed_range_location(), Err(SeedRangeError::UnpairedSeed(55)));
+
+        let almanac = Almanac::from("seeds:\n\nmap:\n50 98 2");
+        assert_eq!(almanac.nearest_seed_range_location(), Err(SeedRangeError::NoSeeds));
+
+        let almanac = Almanac::from("seeds: 98 1 5 0\n\nmap:\n50 98 2");
+        assert_eq!(almanac.nearest_seed_range_location(), Ok(50));
+    }
+
     #[test]
     fn test_almanac_seeds_from_range() {
         let input = "seeds: 79 14 55 13";
@@ -165,4 +286,70 @@ mod tests {
         let result = Almanac::seeds_from_ranges(input);
         assert_eq!(result, output);
     }
+
+    /// Disjoint source ranges moved onto disjoint target ranges in a shuffled order
+    fn map() -> impl Strategy<Value = Map> {
+        prop::collection::vec((0..20u64, 1..20usize), 1..8)
+            .prop_flat_map(|pieces| {
+                let order = Just((0..pieces.len()).collect::<Vec<usize>>()).prop_shuffle();
+                (Just(pieces), order, 0..500u64)
+            })
+            .prop_map(|(pieces, order, target_offset)| {
+                let mut sources = vec![];
+                let mut start = 0;
+                for (gap, len) in &pieces {
+                    sources.push(start + gap);
+                    start += gap + *len as u64;
+                }
+                let mut targets = vec![0; pieces.len()];
+                let mut target = target_offset;
+                for i in order {
+                    targets[i] = target;
+                    target += pieces[i].1 as u64;
+                }
+                Map {
+                    vec: pieces
+                        .iter()
+                        .zip(sources.iter().zip(targets))
+                        .map(|((_, len), (&source_start, target_start))| MapRange { source_start, target_start, len: *len })
+                        .collect(),
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_map_convert_bijection(map in map()) {
+            let sources: Vec<u64> = map.vec
+                .iter()
+                .flat_map(|range| range.source_start..range.source_start + range.len as u64)
+                .collect();
+            let mut converted: Vec<u64> = sources.iter().map(|&id| map.convert(id)).collect();
+            converted.sort_unstable();
+            converted.dedup();
+
+            let mut targets: Vec<u64> = map.vec
+                .iter()
+                .flat_map(|range| range.target_start..range.target_start + range.len as u64)
+                .collect();
+            targets.sort_unstable();
+
+            // every covered id lands on a different target, and every target is hit
+            prop_assert_eq!(converted.len(), sources.len());
+            prop_assert_eq!(converted, targets);
+        }
+
+        #[test]
+        fn test_map_convert_ranges_matches_convert(map in map(), start in 0..200u64, len in 0..100u64) {
+            let mut expected: Vec<u64> = (start..start + len).map(|id| map.convert(id)).collect();
+            expected.sort_unstable();
+            let mut converted: Vec<u64> = map
+                .convert_ranges(&[[start, len]])
+                .into_iter()
+                .flat_map(|range| range[0]..range[0] + range[1])
+                .collect();
+            converted.sort_unstable();
+            prop_assert_eq!(converted, expected);
+        }
+    }
 }
@@ -17,9 +17,31 @@ pub fn solve_puzzle2(input: &str) -> i64 {
     race.n_winning_strategies() as i64
 }
 
+/// Reference for part one that tries every charge time of every race
+pub fn solve_puzzle1_brute_force(input: &str) -> i64 {
+    let races = Races::from(input);
+    races
+        .vec
+        .into_iter()
+        .map(|race| race.n_winning_strategies_brute_force())
+        .product::<usize>() as i64
+}
+
+/// Reference for part two that tries every charge time
+pub fn solve_puzzle2_brute_force(input: &str) -> i64 {
+    let race = Race::from(input);
+    race.n_winning_strategies_brute_force() as i64
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    if std::env::args().any(|arg| arg == "--brute-force") {
+        println!("Solution to puzzle one: {}", solve_puzzle1_brute_force(INPUT));
+        println!("Solution to puzzle two: {}", solve_puzzle2_brute_force(INPUT));
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -30,6 +52,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::{boat_races, Differential};
 
     #[test]
     fn test_example_input1() {
@@ -44,4 +67,25 @@ mod tests {
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_n_winning_strategies_edge_cases() {
+        let count = |allowed_time, record_distance| {
+            let race = Race { allowed_time, record_distance };
+            (race.n_winning_strategies(), race.n_winning_strategies_brute_force())
+        };
+        // a record exactly on a charge time, one at the very top and one out of reach
+        assert_eq!(count(30, 200), (9, 9));
+        assert_eq!(count(10, 24), (1, 1));
+        assert_eq!(count(10, 25), (0, 0));
+        assert_eq!(count(0, 0), (0, 0));
+    }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        // part two joins the races, so two of them keep the brute force quick
+        let differential = Differential::new(2_000, 2);
+        differential.assert_agree("2023 day 6 part 1", boat_races, solve_puzzle1_brute_force, solve_puzzle1);
+        differential.assert_agree("2023 day 6 part 2", boat_races, solve_puzzle2_brute_force, solve_puzzle2);
+    }
 }
@@ -89,10 +89,39 @@ impl Race {
         strategies
     }
 
-    pub fn n_winning_strategies(&self) -> usize {
+    /// Counts the winning strategies by trying every charge time
+    pub fn n_winning_strategies_brute_force(&self) -> usize {
         self.strategies()
             .into_iter()
             .filter(|&strategy| strategy > self.record_distance)
             .count()
     }
+
+    /// Counts the winning strategies between the two roots of
+    /// charge_time * (allowed_time - charge_time) = record_distance
+    pub fn n_winning_strategies(&self) -> usize {
+        let time = self.allowed_time as u128;
+        let record = self.record_distance as u128;
+        let wins = |charge_time: u128| charge_time * (time - charge_time) > record;
+
+        let Some(discriminant) = (time * time).checked_sub(4 * record) else {
+            return 0;
+        };
+        // the integer square root only gets close to the first winning
+        // charge time, the exact one is at most a step away
+        let mut first = (time - discriminant.isqrt()) / 2;
+        while first <= time / 2 && !wins(first) {
+            first += 1;
+        }
+        while first > 0 && wins(first - 1) {
+            first -= 1;
+        }
+
+        // the distances are symmetric around half the allowed time
+        if first > time / 2 {
+            0
+        } else {
+            (time - 2 * first + 1) as usize
+        }
+    }
 }
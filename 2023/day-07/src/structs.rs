@@ -149,6 +149,7 @@ impl From<char> for CardType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_hands_sort() {
@@ -202,4 +203,37 @@ mod tests {
         result.sort();
         assert_eq!(result, output);
     }
+
+    const LABELS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+
+    fn hand() -> impl Strategy<Value = Hand> {
+        (prop::array::uniform5(prop::sample::select(&LABELS[..])), 1..1000u64)
+            .prop_map(|(cards, bid)| Hand { cards, bid })
+    }
+
+    proptest! {
+        #[test]
+        fn test_hand_order_antisymmetric(a in hand(), b in hand()) {
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            // bids play no part in the ranking
+            prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a.cards == b.cards);
+        }
+
+        #[test]
+        fn test_hand_order_transitive(a in hand(), b in hand(), c in hand()) {
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+            if a >= b && b >= c {
+                prop_assert!(a >= c);
+            }
+        }
+
+        #[test]
+        fn test_hand_order_follows_hand_type(a in hand(), b in hand()) {
+            if a.card_type() != b.card_type() {
+                prop_assert_eq!(a.cmp(&b), a.card_type().cmp(&b.card_type()));
+            }
+        }
+    }
 }
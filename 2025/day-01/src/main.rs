@@ -1,91 +1,100 @@
 mod structs;
+use crate::structs::*;
+
+use std::io::BufRead;
 
 pub const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
-fn solve_puzzle1(input: &str) -> i32 {
-    let rotations: Vec<&str> = input
+fn parse_input(input: &str) -> Vec<Rotation> {
+    input
         .lines()
         .filter(|line| !line.is_empty())
-        .collect();
-
-    let mut dial: i32 = 50;
-    let mut zeros: i32 = 0;
-    for r in &rotations {
-        let (direction, distance) = r.split_at(1);
-
-        let steps: i32 = distance.parse()
-            .expect("failed to parse distance as i32");
-
-        match direction {
-            "L" => {
-                dial = ((dial + 100) - steps) % 100
-            }
-            "R" => {
-                dial = (dial + steps) % 100
-            }
-            _ => panic!("invalid direction")
-        }
+        .map(Rotation::from)
+        .collect()
+}
 
-        if dial == 0 {
+fn solve_puzzle1(input: &str) -> u64 {
+    let mut dial = Dial::default();
+    let mut zeros: u64 = 0;
+
+    for rotation in parse_input(input) {
+        dial.rotate(rotation);
+        if dial.position() == 0 {
             zeros += 1
         }
     }
 
-    return zeros
+    zeros
 }
 
-fn solve_puzzle2(input: &str) -> i32 {
-    let rotations: Vec<&str> = input
-        .lines()
-        .filter(|line| !line.is_empty())
-        .collect();
-
-    let mut dial: i32 = 50;
-    let mut zeros: i32 = 0;
-    let mut steps: i32 = 0;
-    let mut direction: &str = "";
-    let mut distance: &str;
+fn solve_puzzle2(input: &str) -> u64 {
+    let mut dial = Dial::default();
 
-    for r in rotations {
-        (direction, distance) = r.split_at(1);
+    parse_input(input)
+        .into_iter()
+        .map(|rotation| dial.rotate(rotation))
+        .sum()
+}
 
-        steps = distance.parse()
-            .expect("failed to parse distance as i32");
-        println!("{:?} Steps in {:?} direction, dial: {:?}, zeros: {:?}", steps, direction, dial, zeros);
+/// Turns the dial one rotation at a time, counting the zeros it
+/// stops at and passes
+fn solve_streaming(reader: impl BufRead) -> (u64, u64) {
+    let mut dial = Dial::default();
+    let (mut zeros, mut passed_zeros): (u64, u64) = (0, 0);
 
-        while steps > 100 {
-            steps -= 100;
-            zeros += 1;
+    for line in reader.lines() {
+        let line = line.expect("failed to read line");
+        if line.is_empty() {
+            continue
         }
-        
-        match direction {
-            "L" => {
-                if dial != 0 && dial < steps {
-                    zeros += 1;
-                }
-                dial = ((dial + 100) - steps) % 100
-            }
-            "R" => {
-                if dial + steps > 100 {
-                    zeros += 1;
-                }
-                dial = (dial + steps) % 100
-            }
-            _ => panic!("invalid direction")
+        passed_zeros += dial.rotate(Rotation::from(line.as_str()));
+        if dial.position() == 0 {
+            zeros += 1
         }
+    }
 
-        if dial == 0 {
-            zeros += 1;
+    (zeros, passed_zeros)
+}
+
+/// Prints the rotation log of part two, e.g. for the
+/// arguments `--log csv --crossing-zero` or `--replay expected.csv`
+fn print_log(input: &str, args: &[String]) {
+    let log = RotationLog::record(&mut Dial::default(), &parse_input(input));
+
+    if let Some(i) = args.iter().position(|arg| arg == "--replay") {
+        let path = args.get(i + 1).expect("--replay should be followed by a path");
+        let expected = std::fs::read_to_string(path).expect("failed to read expected log");
+        match RotationLog::from_csv(&expected).replay(Dial::default()) {
+            Some((logged, replayed)) => println!("Expected {:?}\nbut got  {:?}", logged, replayed),
+            None => println!("Replay matches the expected log"),
         }
+        return;
     }
-    println!("{:?} Steps in {:?} direction, dial: {:?}, zeros: {:?}", steps, direction, dial, zeros);
 
-    return zeros
+    let log = if args.iter().any(|arg| arg == "--crossing-zero") { log.crossing_zero() } else { log };
+    let format = args
+        .iter()
+        .position(|arg| arg == "--log")
+        .and_then(|i| args.get(i + 1));
+    match format.map(|format| format.as_str()) {
+        Some("json") => println!("{}", log.to_json()),
+        _ => print!("{}", log.to_csv()),
+    }
 }
 
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if aoc::run_streaming(&args, solve_streaming) {
+        return;
+    }
+
+    if args.iter().any(|arg| arg == "--log" || arg == "--replay") {
+        print_log(INPUT, &args);
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -99,15 +108,21 @@ mod tests {
 
     #[test]
     fn test_example_input1() {
-        const EXAMPLE_OUTPUT: i32 = 3;
+        const EXAMPLE_OUTPUT: u64 = 3;
         let result = solve_puzzle1(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
 
     #[test]
     fn test_example_input2() {
-        const EXAMPLE_OUTPUT: i32 = 6;
+        const EXAMPLE_OUTPUT: u64 = 6;
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_solve_streaming() {
+        let result = solve_streaming(EXAMPLE_INPUT.as_bytes());
+        assert_eq!(result, (solve_puzzle1(EXAMPLE_INPUT), solve_puzzle2(EXAMPLE_INPUT)));
+    }
 }
@@ -0,0 +1,377 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// A direction other than L or R
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    pub direction: Direction,
+    pub distance: u64,
+}
+
+/// A circular dial with positions 0 to size - 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    size: u64,
+    position: u64,
+}
+
+/// What happened during a single rotation of the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationEvent {
+    pub index: usize,
+    pub direction: Direction,
+    pub distance: u64,
+    pub start: u64,
+    pub end: u64,
+    pub zeros: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RotationLog(pub Vec<RotationEvent>);
+
+impl Direction {
+    pub fn as_char(&self) -> char {
+        match self {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Direction \"{}\" is neither L nor R.", self.0)
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+impl From<&str> for Rotation {
+    fn from(s: &str) -> Self {
+        let s = s.trim();
+        let split = s.char_indices().nth(1).map_or(s.len(), |(i, _)| i);
+        let (direction, distance) = s.split_at(split);
+
+        let direction: Direction = direction.parse().unwrap_or_else(|err| panic!("{}", err));
+        let distance: u64 = distance.parse()
+            .expect("failed to parse distance as u64");
+
+        Rotation { direction, distance }
+    }
+}
+
+impl Default for Dial {
+    /// The dial of the puzzle: 100 positions, starting at 50
+    fn default() -> Self {
+        Dial::new(100, 50)
+    }
+}
+
+impl Dial {
+    pub fn new(size: u64, start: u64) -> Dial {
+        assert!(size > 0, "dial should have at least one position");
+        Dial { size, position: start % size }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Turns the dial and returns how often it pointed at zero
+    /// during the rotation, including where it stops
+    pub fn rotate(&mut self, rotation: Rotation) -> u64 {
+        let steps = rotation.distance % self.size;
+
+        match rotation.direction {
+            Direction::Right => {
+                // zero is reached after size - position steps, then every size steps
+                let zeros = (self.position as u128 + rotation.distance as u128) / self.size as u128;
+                // subtracting first keeps huge dials from overflowing
+                let steps_to_zero = self.size - self.position;
+                self.position = if steps >= steps_to_zero { steps - steps_to_zero } else { self.position + steps };
+                zeros as u64
+            }
+            Direction::Left => {
+                // turning left from position p passes zeros like turning right
+                // from the mirrored position size - p, with zero mirrored onto itself
+                let distance_to_zero = (self.size - self.position) % self.size;
+                let zeros = (distance_to_zero as u128 + rotation.distance as u128) / self.size as u128;
+                self.position = if steps > self.position {
+                    self.size - (steps - self.position)
+                } else {
+                    self.position - steps
+                };
+                zeros as u64
+            }
+        }
+    }
+}
+
+impl RotationLog {
+    /// Applies all rotations to the dial, logging each of them
+    pub fn record(dial: &mut Dial, rotations: &[Rotation]) -> RotationLog {
+        let events = rotations
+            .iter()
+            .enumerate()
+            .map(|(index, rotation)| {
+                let start = dial.position();
+                let zeros = dial.rotate(*rotation);
+                RotationEvent {
+                    index,
+                    direction: rotation.direction,
+                    distance: rotation.distance,
+                    start,
+                    end: dial.position(),
+                    zeros,
+                }
+            })
+            .collect();
+        RotationLog(events)
+    }
+
+    /// The rotations that produced this log
+    pub fn rotations(&self) -> Vec<Rotation> {
+        self.0
+            .iter()
+            .map(|event| Rotation { direction: event.direction, distance: event.distance })
+            .collect()
+    }
+
+    /// Only the rotations that passed or stopped at zero
+    pub fn crossing_zero(&self) -> RotationLog {
+        RotationLog(self.0.iter().filter(|event| event.zeros > 0).copied().collect())
+    }
+
+    /// Replays the logged rotations on a dial of the same size as the given one
+    /// and returns the first logged event that differs from the replayed one.
+    ///
+    /// A rotation starts where the one logged right before it ended, or at its
+    /// logged start if that one is missing, so filtered logs can be replayed too.
+    pub fn replay(&self, dial: Dial) -> Option<(RotationEvent, RotationEvent)> {
+        let mut previous: Option<RotationEvent> = None;
+        for (logged, rotation) in self.0.iter().zip(self.rotations()) {
+            let start = match previous {
+                Some(previous) if previous.index + 1 == logged.index => previous.end,
+                _ => logged.start,
+            };
+            let mut dial = Dial::new(dial.size, start);
+            let zeros = dial.rotate(rotation);
+            let replayed = RotationEvent { start, end: dial.position(), zeros, ..*logged };
+            if replayed != *logged {
+                return Some((*logged, replayed));
+            }
+            previous = Some(*logged);
+        }
+        None
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("index,direction,distance,start,end,zeros\n");
+        for event in &self.0 {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                event.index, event.direction.as_char(), event.distance, event.start, event.end, event.zeros
+            ));
+        }
+        csv
+    }
+
+    /// Reads a log written by to_csv
+    pub fn from_csv(csv: &str) -> RotationLog {
+        let events = csv
+            .lines()
+            .skip(1)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                let [index, direction, distance, start, end, zeros] = fields[..] else {
+                    panic!("log line should have six fields")
+                };
+                let parse = |field: &str| -> u64 {
+                    field.parse().expect("failed to parse log field as u64")
+                };
+                RotationEvent {
+                    index: index.parse().expect("failed to parse index as usize"),
+                    direction: direction.parse().unwrap_or_else(|err| panic!("{}", err)),
+                    distance: parse(distance),
+                    start: parse(start),
+                    end: parse(end),
+                    zeros: parse(zeros),
+                }
+            })
+            .collect();
+        RotationLog(events)
+    }
+
+    pub fn to_json(&self) -> String {
+        let events: Vec<String> = self.0
+            .iter()
+            .map(|event| {
+                format!(
+                    "{{\"index\":{},\"direction\":\"{}\",\"distance\":{},\"start\":{},\"end\":{},\"zeros\":{}}}",
+                    event.index, event.direction.as_char(), event.distance, event.start, event.end, event.zeros
+                )
+            })
+            .collect();
+        format!("[{}]", events.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference implementation moving the dial one click at a time
+    fn rotate_step_by_step(dial: &mut Dial, rotation: Rotation) -> u64 {
+        let mut zeros = 0;
+        for _ in 0..rotation.distance {
+            dial.position = match rotation.direction {
+                Direction::Left => (dial.position + dial.size - 1) % dial.size,
+                Direction::Right => (dial.position + 1) % dial.size,
+            };
+            if dial.position == 0 {
+                zeros += 1;
+            }
+        }
+        zeros
+    }
+
+    #[test]
+    fn test_rotation_from_string() {
+        assert_eq!(Rotation::from("L68"), Rotation { direction: Direction::Left, distance: 68 });
+        assert_eq!(Rotation::from("R1000"), Rotation { direction: Direction::Right, distance: 1000 });
+    }
+
+    #[test]
+    fn test_direction_from_string() {
+        assert_eq!("L".parse(), Ok(Direction::Left));
+        assert_eq!("R".parse(), Ok(Direction::Right));
+        assert_eq!("U".parse::<Direction>(), Err(ParseDirectionError("U".to_string())));
+        assert_eq!("".parse::<Direction>(), Err(ParseDirectionError("".to_string())));
+    }
+
+    #[test]
+    fn test_dial_rotate_matches_step_by_step() {
+        for size in 1..=12 {
+            for start in 0..size {
+                for distance in 0..=4 * size + 1 {
+                    for direction in [Direction::Left, Direction::Right] {
+                        let rotation = Rotation { direction, distance };
+                        let mut dial = Dial::new(size, start);
+                        let mut reference = Dial::new(size, start);
+
+                        let zeros = dial.rotate(rotation);
+                        let reference_zeros = rotate_step_by_step(&mut reference, rotation);
+                        assert_eq!((zeros, dial), (reference_zeros, reference), "{:?} from {} on a dial of {}", rotation, start, size);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dial_large_rotations() {
+        let mut dial = Dial::default();
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Right, distance: 1000 }), 10);
+        assert_eq!(dial.position(), 50);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Left, distance: 10_u64.pow(15) + 50 }), 10_u64.pow(13) + 1);
+        assert_eq!(dial.position(), 0);
+
+        let mut dial = Dial::new(3, 2);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Right, distance: u64::MAX }), u64::MAX / 3);
+    }
+
+    #[test]
+    fn test_dial_huge_sizes() {
+        let size = u64::MAX / 2 + 10;
+        let mut dial = Dial::new(size, size - 3);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Right, distance: size - 5 }), 1);
+        assert_eq!(dial.position(), size - 8);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Right, distance: 10 }), 1);
+        assert_eq!(dial.position(), 2);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Left, distance: size - 1 }), 1);
+        assert_eq!(dial.position(), 3);
+
+        let mut dial = Dial::new(u64::MAX, 1);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Left, distance: u64::MAX - 1 }), 1);
+        assert_eq!(dial.position(), 2);
+        assert_eq!(dial.rotate(Rotation { direction: Direction::Right, distance: u64::MAX - 2 }), 1);
+        assert_eq!(dial.position(), 0);
+    }
+
+    #[test]
+    fn test_rotation_log() {
+        let rotations: Vec<Rotation> = ["L68", "L30", "R48"].into_iter().map(Rotation::from).collect();
+        let log = RotationLog::record(&mut Dial::default(), &rotations);
+        let output = vec![
+            RotationEvent { index: 0, direction: Direction::Left, distance: 68, start: 50, end: 82, zeros: 1 },
+            RotationEvent { index: 1, direction: Direction::Left, distance: 30, start: 82, end: 52, zeros: 0 },
+            RotationEvent { index: 2, direction: Direction::Right, distance: 48, start: 52, end: 0, zeros: 1 },
+        ];
+        assert_eq!(log.0, output);
+        assert_eq!(log.rotations(), rotations);
+        assert_eq!(log.crossing_zero().0, vec![output[0], output[2]]);
+    }
+
+    #[test]
+    fn test_rotation_log_exports() {
+        let rotations: Vec<Rotation> = ["L68", "R48"].into_iter().map(Rotation::from).collect();
+        let log = RotationLog::record(&mut Dial::default(), &rotations);
+
+        let csv = "index,direction,distance,start,end,zeros\n0,L,68,50,82,1\n1,R,48,82,30,1\n";
+        assert_eq!(log.to_csv(), csv);
+        assert_eq!(RotationLog::from_csv(csv), log);
+
+        let json = r#"[{"index":0,"direction":"L","distance":68,"start":50,"end":82,"zeros":1},{"index":1,"direction":"R","distance":48,"start":82,"end":30,"zeros":1}]"#;
+        assert_eq!(log.to_json(), json);
+    }
+
+    #[test]
+    fn test_rotation_log_replay() {
+        let csv = "index,direction,distance,start,end,zeros\n0,L,68,50,82,1\n1,R,48,82,30,0\n";
+        let log = RotationLog::from_csv(csv);
+        let (logged, replayed) = log.replay(Dial::default()).unwrap();
+        assert_eq!(logged.index, 1);
+        assert_eq!((logged.zeros, replayed.zeros), (0, 1));
+
+        let log = RotationLog::record(&mut Dial::default(), &log.rotations());
+        assert_eq!(log.replay(Dial::default()), None);
+
+        // a rotation right after another has to start where that one ended
+        let csv = "index,direction,distance,start,end,zeros\n0,L,68,50,82,1\n1,R,48,80,28,1\n";
+        let (logged, replayed) = RotationLog::from_csv(csv).replay(Dial::default()).unwrap();
+        assert_eq!((logged.start, replayed.start), (80, 82));
+    }
+
+    #[test]
+    fn test_rotation_log_replay_filtered() {
+        let rotations: Vec<Rotation> = ["L68", "L30", "R48", "L5", "R60"].into_iter().map(Rotation::from).collect();
+        let log = RotationLog::record(&mut Dial::default(), &rotations).crossing_zero();
+        assert_eq!(log.0.iter().map(|event| event.index).collect::<Vec<usize>>(), vec![0, 2, 4]);
+        assert_eq!(log.replay(Dial::default()), None);
+
+        let mut tampered = log.clone();
+        tampered.0[1].zeros = 2;
+        let (logged, replayed) = tampered.replay(Dial::default()).unwrap();
+        assert_eq!((logged.index, replayed.zeros), (2, 1));
+    }
+}
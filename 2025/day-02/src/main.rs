@@ -1,84 +1,122 @@
 mod structs;
+use crate::structs::*;
 
 pub const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
-fn parse_ranges(s: &str) -> Vec<(&str, &str)> {
+fn parse_ranges(s: &str, radix: u32) -> Vec<(u128, u128)> {
     s.trim_end()
         .split(",")
         .map(|substring| {
-            substring.split_once("-")
-                .expect("range has more than one - delimiter")
+            let (start, end) = substring.split_once("-")
+                .expect("range has more than one - delimiter");
+            let start = u128::from_str_radix(start, radix).expect("failed to parse start to int");
+            let end = u128::from_str_radix(end, radix).expect("failed to parse end to int");
+            (start, end)
         })
         .collect()
 }
 
+/// Checks whether the digits, in any radix, are a block repeated twice
 fn is_invalid(s: &str) -> bool {
-    if s.len() % 2 != 0 {
+    if !s.len().is_multiple_of(2) {
         return false
     }
 
-    let (a, b) = s.split_at((s.len() / 2) as usize);
-    return a == b
+    let (a, b) = s.split_at(s.len() / 2);
+    a == b
 }
 
+/// Checks whether the digits, in any radix, are a block repeated at least twice
 fn is_invalid2(s: &str) -> bool {
     let mut substring = String::new();
     'chars: for c in s.chars().take(s.chars().count() / 2) {
         substring.push(c);
 
-        if s.len() % substring.len() != 0 {
+        if !s.len().is_multiple_of(substring.len()) {
             continue
         }
 
         for i in (0..s.len()).step_by(substring.len()) {
-            if substring != &s[i..i+substring.len()] {
+            if substring != s[i..i+substring.len()] {
                 continue 'chars
             }
         }
         return true
     }
-    return false
+    false
 }
 
-fn solve_puzzle1(input: &str) -> i64 {
-    let mut sum = 0;
+/// Sums the ids whose digits match the predicate by checking every id
+/// in every range. Returns None if the sum does not fit into a u128
+fn sum_invalid_brute_force(input: &str, radix: u32, is_invalid: fn(&str) -> bool) -> Option<u128> {
+    let mut sum: u128 = 0;
 
-    for (start, end) in parse_ranges(input) {
-        println!("{:?}, {:?}", start, end);
-        let start_int: usize = start.parse().expect("failed to parse start to int");
-        let end_int: usize = end.parse().expect("failed to parse end to int");
-
-        for i in start_int..=end_int {
-            if is_invalid(&i.to_string()) {
-                sum += i
+    for (start, end) in parse_ranges(input, radix) {
+        for i in start..=end {
+            if is_invalid(&to_digits(i, radix)) {
+                sum = sum.checked_add(i)?;
             }
         }
     }
 
-    return sum as i64
+    Some(sum)
 }
 
-fn solve_puzzle2(input: &str) -> i64 {
-    let mut sum = 0;
-
-    for (start, end) in parse_ranges(input) {
-        println!("{:?}, {:?}", start, end);
-        let start_int: usize = start.parse().expect("failed to parse start to int");
-        let end_int: usize = end.parse().expect("failed to parse end to int");
+/// Sums the invalid ids by constructing them directly.
+/// Returns None if the sum does not fit into a u128
+fn sum_invalid(input: &str, repeats: Repeats, radix: u32) -> Option<u128> {
+    let mut sum: u128 = 0;
 
-        for i in start_int..=end_int {
-            if is_invalid2(&i.to_string()) {
-                sum += i;
-            }
+    for (start, end) in parse_ranges(input, radix) {
+        for id in repeated_ids(start, end, repeats, radix) {
+            sum = sum.checked_add(id)?;
         }
     }
 
-    return sum as i64
+    Some(sum)
+}
+
+fn solve_puzzle1(input: &str) -> u128 {
+    sum_invalid(input, Repeats::Twice, 10).expect("sum of invalid ids overflows u128")
+}
+
+fn solve_puzzle2(input: &str) -> u128 {
+    sum_invalid(input, Repeats::AtLeastTwice, 10).expect("sum of invalid ids overflows u128")
 }
 
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    // the brute force path checks every single id
+    let brute_force = args.iter().any(|arg| arg == "--brute-force");
+    let radix: u32 = args
+        .iter()
+        .position(|arg| arg == "--radix")
+        .map(|i| args.get(i + 1).expect("--radix should be followed by a number"))
+        .map(|radix| radix.parse().expect("failed to parse radix as u32"))
+        .unwrap_or(10);
+
+    if brute_force || radix != 10 {
+        let solution1 = if brute_force {
+            sum_invalid_brute_force(INPUT, radix, is_invalid)
+        } else {
+            sum_invalid(INPUT, Repeats::Twice, radix)
+        };
+        let solution2 = if brute_force {
+            sum_invalid_brute_force(INPUT, radix, is_invalid2)
+        } else {
+            sum_invalid(INPUT, Repeats::AtLeastTwice, radix)
+        };
+        for (name, solution) in [("one", solution1), ("two", solution2)] {
+            match solution {
+                Some(solution) => println!("Solution to puzzle {}: {}", name, solution),
+                None => println!("Solution to puzzle {} overflows u128", name),
+            }
+        }
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -89,10 +127,12 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use aoc::{id_ranges, Differential};
 
     #[test]
     fn test_example_input1() {
-        const EXAMPLE_OUTPUT: i64 = 1227775554;
+        const EXAMPLE_OUTPUT: u128 = 1227775554;
         let result = solve_puzzle1(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
@@ -105,10 +145,76 @@ mod tests {
         assert!(is_invalid2("1111111"));
     }
 
+    #[test]
+    fn test_repeated_ids_match_brute_force() {
+        for radix in [2, 3, 10, 16] {
+            for start in (0..3000).step_by(37) {
+                for end in [start, start + 50, start + 999] {
+                    let ranges = format!("{}-{}", to_digits(start, radix), to_digits(end, radix));
+                    assert_eq!(
+                        sum_invalid(&ranges, Repeats::Twice, radix),
+                        sum_invalid_brute_force(&ranges, radix, is_invalid),
+                        "{} in radix {}", ranges, radix
+                    );
+                    assert_eq!(
+                        sum_invalid(&ranges, Repeats::AtLeastTwice, radix),
+                        sum_invalid_brute_force(&ranges, radix, is_invalid2),
+                        "{} in radix {}", ranges, radix
+                    );
+                }
+            }
+        }
+        assert_eq!(Some(solve_puzzle2(EXAMPLE_INPUT)), sum_invalid_brute_force(EXAMPLE_INPUT, 10, is_invalid2));
+    }
+
+    #[test]
+    fn test_sum_invalid_hexadecimal_and_overflow() {
+        assert_eq!(sum_invalid("a0-bf,ab00-abff", Repeats::Twice, 16), Some(0xaa + 0xbb + 0xabab));
+        assert_eq!(sum_invalid("1-1111", Repeats::AtLeastTwice, 2), Some(3 + 7 + 10 + 15));
+
+        let max = "f".repeat(32);
+        let ranges = format!("{}-{},{}-{}", max, max, max, max);
+        assert_eq!(sum_invalid(&ranges, Repeats::Twice, 16), None);
+    }
+
     #[test]
     fn test_example_input2() {
-        const EXAMPLE_OUTPUT: i64 = 4174379265;
+        const EXAMPLE_OUTPUT: u128 = 4174379265;
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        let differential = Differential::new(1_000, 4);
+        differential.assert_agree(
+            "2025 day 2 part 1",
+            id_ranges,
+            |input| sum_invalid_brute_force(input, 10, is_invalid),
+            |input| sum_invalid(input, Repeats::Twice, 10),
+        );
+        differential.assert_agree(
+            "2025 day 2 part 2",
+            id_ranges,
+            |input| sum_invalid_brute_force(input, 10, is_invalid2),
+            |input| sum_invalid(input, Repeats::AtLeastTwice, 10),
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_is_invalid_implies_is_invalid2(id in 1..u64::MAX) {
+            let id = id.to_string();
+            prop_assert!(!is_invalid(&id) || is_invalid2(&id));
+        }
+
+        #[test]
+        fn test_repeated_blocks_are_invalid(block in 1..100_000u64, repeats in 2..5usize) {
+            let id = block.to_string().repeat(repeats);
+            prop_assert!(is_invalid2(&id));
+            if repeats.is_multiple_of(2) {
+                prop_assert!(is_invalid(&id));
+            }
+        }
+    }
 }
@@ -0,0 +1,105 @@
+/// How often a block of digits has to be repeated
+/// for an id to be invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeats {
+    Twice,
+    AtLeastTwice,
+}
+
+fn digit_count(n: u128, radix: u32) -> u32 {
+    n.checked_ilog(radix as u128).unwrap_or(0) + 1
+}
+
+/// Writes n in the given radix, e.g. "ff" for 255 in radix 16
+pub fn to_digits(mut n: u128, radix: u32) -> String {
+    let mut digits: Vec<char> = vec![];
+    loop {
+        let digit = (n % radix as u128) as u32;
+        digits.push(char::from_digit(digit, radix).expect("radix should be between 2 and 36"));
+        n /= radix as u128;
+        if n == 0 {
+            break
+        }
+    }
+    digits.into_iter().rev().collect()
+}
+
+/// Constructs all ids between start and end (inclusive) that consist of a
+/// block of digits in the given radix repeated according to repeats,
+/// in ascending order.
+///
+/// An id with k blocks of length l equals block * (1 + r^l + ... + r^((k-1)l)),
+/// so for every length and repeat count the valid blocks form a range.
+pub fn repeated_ids(start: u128, end: u128, repeats: Repeats, radix: u32) -> Vec<u128> {
+    assert!((2..=36).contains(&radix), "radix should be between 2 and 36");
+    let mut ids: Vec<u128> = vec![];
+    if start > end {
+        return ids
+    }
+    let r = radix as u128;
+
+    for total_len in digit_count(start, radix)..=digit_count(end, radix) {
+        for block_len in 1..=total_len / 2 {
+            if !total_len.is_multiple_of(block_len) {
+                continue
+            }
+            let k = total_len / block_len;
+            if repeats == Repeats::Twice && k != 2 {
+                continue
+            }
+
+            // every term is at most r^(total_len - 1) <= end, so none of this overflows
+            let multiplier: u128 = (0..k).map(|i| r.pow(i * block_len)).sum();
+            let min_block = r.pow(block_len - 1).max(start.div_ceil(multiplier));
+            let max_block = (r.pow(block_len) - 1).min(end / multiplier);
+            for block in min_block..=max_block {
+                ids.push(block * multiplier);
+            }
+        }
+    }
+
+    // e.g. 111111 is 1 six times, 11 three times and 111 twice
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_ids() {
+        assert_eq!(repeated_ids(11, 22, Repeats::Twice, 10), vec![11, 22]);
+        assert_eq!(repeated_ids(95, 115, Repeats::AtLeastTwice, 10), vec![99, 111]);
+        assert_eq!(repeated_ids(1, 9, Repeats::AtLeastTwice, 10), Vec::<u128>::new());
+        assert_eq!(repeated_ids(111110, 111112, Repeats::AtLeastTwice, 10), vec![111111]);
+        assert_eq!(repeated_ids(2121212118, 2121212124, Repeats::AtLeastTwice, 10), vec![2121212121]);
+    }
+
+    #[test]
+    fn test_repeated_ids_binary_and_hexadecimal() {
+        // 11, 111, 1010 and 1111 in binary
+        assert_eq!(repeated_ids(1, 15, Repeats::Twice, 2), vec![3, 10, 15]);
+        assert_eq!(repeated_ids(1, 15, Repeats::AtLeastTwice, 2), vec![3, 7, 10, 15]);
+        // aa, bb and abab in hexadecimal
+        assert_eq!(repeated_ids(0xa0, 0xbf, Repeats::Twice, 16), vec![0xaa, 0xbb]);
+        assert_eq!(repeated_ids(0xab00, 0xabff, Repeats::Twice, 16), vec![0xabab]);
+    }
+
+    #[test]
+    fn test_repeated_ids_beyond_u64() {
+        let id: u128 = 1234567890112345678901;
+        assert_eq!(repeated_ids(id - 1, id + 9, Repeats::Twice, 10), vec![id]);
+        assert_eq!(repeated_ids(u128::MAX - 1, u128::MAX, Repeats::AtLeastTwice, 16), vec![u128::MAX]);
+        assert_eq!(repeated_ids(u128::MAX - 1, u128::MAX, Repeats::AtLeastTwice, 2), vec![u128::MAX]);
+    }
+
+    #[test]
+    fn test_to_digits() {
+        assert_eq!(to_digits(0, 10), "0");
+        assert_eq!(to_digits(10, 2), "1010");
+        assert_eq!(to_digits(0xabab, 16), "abab");
+        assert_eq!(to_digits(u128::MAX, 16), "f".repeat(32));
+    }
+}
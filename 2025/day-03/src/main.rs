@@ -1,80 +1,51 @@
 mod structs;
+use crate::structs::*;
 
-pub const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
-
-fn solve_puzzle1(input: &str) -> i64 {
-    let banks: Vec<&str> = input.lines().collect();
-
-    let mut sum = 0;
-    for bank in banks {
-        let mut highest_i = 0;
-        let mut highest_digit = 0;
-        let mut second_highest_digit = 0;
-
-        for (i, c) in bank.chars().take(bank.chars().count() - 1).enumerate() {
-            let digit = c.to_digit(10).expect("failed to cast to digit");
-            if digit > highest_digit {
-                highest_digit = digit;
-                highest_i = i;
-            }
-        }
+use std::io::BufRead;
 
-        for (i, c) in bank.chars().enumerate() {
-            let digit = c.to_digit(10).expect("failed to cast to digit");
-            if i > highest_i && digit > second_highest_digit {
-                second_highest_digit = digit;
-            }
-        }
+pub const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
-        let mut number = highest_digit.to_string();
-        println!("number: {:?}", number);
-        println!("second_highest_digit: {:?}", second_highest_digit);
-        number.push_str(&second_highest_digit.to_string());
-        println!("number: {:?}", number);
-        sum += number.parse::<i64>().expect("failed to parse number to i64");
-    }
-    sum
+fn sum_max_subsequences(input: &str, k: usize) -> u128 {
+    input
+        .lines()
+        .filter(|bank| !bank.is_empty())
+        .map(|bank| {
+            max_subsequence(bank, k).unwrap_or_else(|err| panic!("{}", err))
+        })
+        .sum()
 }
 
-fn solve_puzzle2(input: &str) -> i64 {
-    let banks: Vec<&str> = input.lines().collect();
-    let mut sum = 0;
-
-    for bank in banks {
-        let mut buffer_size: usize = bank.len() - 12;
-        let mut digits = String::new();
-
-        let mut j = 0;
-        while j < bank.chars().count() {
-            let mut highest_i = 0;
-            let mut highest_digit = 0;
-
-            for (i, c) in bank.chars().skip(j).take(buffer_size + 1).enumerate() {
-                let digit = c.to_digit(10).expect("failed to cast to digit");
-                if digit > highest_digit {
-                    highest_digit = digit;
-                    highest_i = i;
-                }
-            }
-            buffer_size -= highest_i;
-            j += highest_i;
+fn solve_puzzle1(input: &str) -> u128 {
+    sum_max_subsequences(input, 2)
+}
 
-            digits.push_str(&highest_digit.to_string());
+fn solve_puzzle2(input: &str) -> u128 {
+    sum_max_subsequences(input, 12)
+}
 
-            j += 1;
+/// Sums the joltages of both parts one bank at a time
+fn solve_streaming(reader: impl BufRead) -> (u128, u128) {
+    let mut solutions: (u128, u128) = (0, 0);
+    for bank in reader.lines() {
+        let bank = bank.expect("failed to read line");
+        if bank.is_empty() {
+            continue
         }
-        digits = digits[..digits.len() - buffer_size].to_string();
-        
-        println!("bank: {}, digits: {}", bank, digits);
-        sum += digits.parse::<i64>().expect("failed to parse digits to i64");
+        let joltage = |k| max_subsequence(&bank, k).unwrap_or_else(|err| panic!("{}", err));
+        solutions.0 += joltage(2);
+        solutions.1 += joltage(12);
     }
-
-    sum
+    solutions
 }
 
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
+    let args: Vec<String> = std::env::args().collect();
+    if aoc::run_streaming(&args, solve_streaming) {
+        return;
+    }
+
     let solution1 = solve_puzzle1(INPUT);
     println!("Solution to puzzle one: {}", solution1);
 
@@ -88,15 +59,21 @@ mod tests {
 
     #[test]
     fn test_example_input1() {
-        const EXAMPLE_OUTPUT: i64 = 357;
+        const EXAMPLE_OUTPUT: u128 = 357;
         let result = solve_puzzle1(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
 
     #[test]
     fn test_example_input2() {
-        const EXAMPLE_OUTPUT: i64 = 3121910778619;
+        const EXAMPLE_OUTPUT: u128 = 3121910778619;
         let result = solve_puzzle2(EXAMPLE_INPUT);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_solve_streaming() {
+        let result = solve_streaming(EXAMPLE_INPUT.as_bytes());
+        assert_eq!(result, (357, 3121910778619));
+    }
 }
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MaxSubsequenceError {
+    /// More digits were requested than the bank has
+    TooManyDigits { k: usize, bank_len: usize },
+    InvalidDigit(char),
+    /// The resulting number does not fit into a u128
+    Overflow,
+}
+
+impl Display for MaxSubsequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaxSubsequenceError::TooManyDigits { k, bank_len } => {
+                write!(f, "Cannot pick {} digits from a bank of {} batteries.", k, bank_len)
+            }
+            MaxSubsequenceError::InvalidDigit(c) => write!(f, "Battery \"{}\" is not a digit.", c),
+            MaxSubsequenceError::Overflow => write!(f, "The picked digits do not fit into a u128."),
+        }
+    }
+}
+
+/// The largest number formed by k digits of the bank, keeping their order.
+///
+/// Keeps a stack of digits that is popped whenever a larger digit
+/// arrives, as long as enough digits remain to fill all k places.
+pub fn max_subsequence(bank: &str, k: usize) -> Result<u128, MaxSubsequenceError> {
+    let digits: Vec<u32> = bank
+        .chars()
+        .map(|c| c.to_digit(10).ok_or(MaxSubsequenceError::InvalidDigit(c)))
+        .collect::<Result<_, _>>()?;
+    if k > digits.len() {
+        return Err(MaxSubsequenceError::TooManyDigits { k, bank_len: digits.len() })
+    }
+
+    let mut droppable = digits.len() - k;
+    let mut stack: Vec<u32> = Vec::with_capacity(digits.len());
+    for digit in digits {
+        while droppable > 0 && stack.last().is_some_and(|&top| top < digit) {
+            stack.pop();
+            droppable -= 1;
+        }
+        stack.push(digit);
+    }
+    stack.truncate(k);
+
+    stack.into_iter().try_fold(0_u128, |number, digit| {
+        number
+            .checked_mul(10)
+            .and_then(|number| number.checked_add(digit as u128))
+            .ok_or(MaxSubsequenceError::Overflow)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tries every subset of k digits
+    fn max_subsequence_brute_force(bank: &str, k: usize) -> u128 {
+        let digits: Vec<u128> = bank.chars().map(|c| c.to_digit(10).unwrap() as u128).collect();
+        (0_u32..1 << digits.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                digits
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .fold(0, |number, (_, digit)| number * 10 + digit)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_max_subsequence() {
+        assert_eq!(max_subsequence("987654321111111", 2), Ok(98));
+        assert_eq!(max_subsequence("811111111111119", 12), Ok(811111111119));
+        assert_eq!(max_subsequence("234234234234278", 12), Ok(434234234278));
+        assert_eq!(max_subsequence("12", 0), Ok(0));
+        assert_eq!(max_subsequence("12", 2), Ok(12));
+    }
+
+    #[test]
+    fn test_max_subsequence_errors() {
+        assert_eq!(max_subsequence("123", 4), Err(MaxSubsequenceError::TooManyDigits { k: 4, bank_len: 3 }));
+        assert_eq!(max_subsequence("1a3", 2), Err(MaxSubsequenceError::InvalidDigit('a')));
+        assert_eq!(max_subsequence(&"9".repeat(40), 40), Err(MaxSubsequenceError::Overflow));
+    }
+
+    #[test]
+    fn test_max_subsequence_matches_brute_force() {
+        // every bank of up to 7 digits drawn from a small alphabet
+        for len in 0..=7_u32 {
+            for code in 0..4_u32.pow(len) {
+                let bank: String = (0..len)
+                    .map(|i| ['1', '3', '5', '9'][(code / 4_u32.pow(i) % 4) as usize])
+                    .collect();
+                for k in 0..=len as usize {
+                    assert_eq!(max_subsequence(&bank, k), Ok(max_subsequence_brute_force(&bank, k)), "{} {}", bank, k);
+                }
+            }
+        }
+    }
+}
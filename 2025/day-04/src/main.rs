@@ -1,79 +1,38 @@
 mod structs;
+use crate::structs::*;
 
 use std::fs::File;
 use std::io::Write;
-use std::time::Instant;
-use rayon::prelude::*;
-
-fn parse_input(input: &str) -> Vec<Vec<i32>> { 
-    input.trim_end()
-        .lines()
-        .map(|line| {
-            line.chars().map(|c| {
-                match c {
-                    '.' => 0,
-                    '@' => 1,
-                    _ => panic!("unexpected char in input"),
-                }
-            }).collect()
-        })
-        .collect()
-}
-
-fn removable_rolls(matrix: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    let row_count = matrix.len();
-    let col_count = matrix[0].len();
-
-    let mut result_matrix = matrix.clone();
+use std::time::{Duration, Instant};
 
-    result_matrix.iter_mut().enumerate().for_each(|(i, row)| {
-        for j in 0..col_count {
-            let mut adjacency = 0;
-
-            let start_k = if i == 0 { 0 } else { i - 1 };
-            for k in start_k..(i + 2).min(row_count) {
-                let start_l = if j == 0 { 0 } else { j - 1 };
-                for l in start_l..(j + 2).min(col_count) {
-                    adjacency += matrix[k][l];
-                }
-            }
-
-            if adjacency < 5 && row[j] == 1 {
-                row[j] = 1;
-            } else {
-                row[j] = 0;
-            }
-        }
-    });
-
-    result_matrix
+fn parse_input(input: &str) -> BitGrid {
+    input.parse().unwrap_or_else(|err| panic!("{}", err))
 }
 
-fn solve_puzzle1(matrix: &Vec<Vec<i32>>) -> i32 {
-    let result_matrix = removable_rolls(matrix);
-    result_matrix.iter().flatten().sum()
+fn solve_puzzle1(grid: &BitGrid) -> i32 {
+    grid.removable().count_ones() as i32
 }
 
-fn solve_puzzle2(matrix: &Vec<Vec<i32>>) -> i32 {
+/// Reference for part two that rescans the whole grid every round
+fn solve_puzzle2_brute_force(grid: &BitGrid) -> i32 {
     let mut roll_count = 1;
     let mut total_roll_count = 0;
-    let mut intermediate_matrix = matrix.clone();
-    let col_count = matrix[0].len();
+    let mut intermediate_grid = grid.clone();
 
     while roll_count > 0 {
-        let roll_matrix = removable_rolls(&intermediate_matrix);
-        roll_count = roll_matrix.iter().flatten().sum();
+        let roll_grid = intermediate_grid.removable();
+        roll_count = roll_grid.count_ones() as i32;
         total_roll_count += roll_count;
-        intermediate_matrix.par_iter_mut().enumerate().for_each(|(i, row)| {
-            for j in 0..col_count {
-                row[j] = row[j] - roll_matrix[i][j];
-            }
-        })
+        intermediate_grid.remove(&roll_grid);
     }
 
     total_roll_count
 }
 
+fn solve_puzzle2(grid: &BitGrid) -> i32 {
+    simulate_removal(grid).total_removed() as i32
+}
+
 fn main() {
     const INPUT: &str = include_str!("../data/input1.txt");
 
@@ -81,19 +40,57 @@ fn main() {
         .expect("failed to create file handle");
 
     let parse_timer = Instant::now();
-    let parsed_input = parse_input(INPUT);
+    let grid = parse_input(INPUT);
     let parse_time = parse_timer.elapsed();
 
     let timer1 = Instant::now();
-    let solution1 = solve_puzzle1(&parsed_input);
+    let solution1 = solve_puzzle1(&grid);
     let time1 = timer1.elapsed();
     println!("Solution to puzzle one: {}", solution1);
 
     let timer2 = Instant::now();
-    let solution2 = solve_puzzle2(&parsed_input);
+    let solution2 = if std::env::args().any(|arg| arg == "--brute-force") {
+        solve_puzzle2_brute_force(&grid)
+    } else {
+        solve_puzzle2(&grid)
+    };
     let time2 = timer2.elapsed();
     println!("Solution to puzzle two: {}", solution2);
 
+    if std::env::args().any(|arg| arg == "--order") {
+        let removal = simulate_removal(&grid);
+        println!("Removed {} rolls in {} rounds:", removal.total_removed(), removal.round_count());
+        for (i, j) in removal.order() {
+            println!("{},{}", i, j);
+        }
+    }
+
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--animate") {
+        let frames = simulate_removal(&grid).frames(&grid);
+        match args.get(i + 1).map(String::as_str) {
+            Some("terminal") => {
+                for frame in &frames {
+                    print!("\x1b[2J\x1b[H{}", frame);
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+            }
+            Some("pbm") => {
+                let dir = args.get(i + 2).expect("--animate pbm should be followed by a directory");
+                std::fs::create_dir_all(dir).expect("failed to create frame directory");
+                for (index, frame) in frames.iter().enumerate() {
+                    std::fs::write(format!("{}/frame_{:04}.pbm", dir, index), frame.to_pbm())
+                        .expect("failed to write frame");
+                }
+            }
+            Some("cast") => {
+                let path = args.get(i + 2).expect("--animate cast should be followed by a file name");
+                std::fs::write(path, to_cast(&frames, 0.2)).expect("failed to write cast file");
+            }
+            _ => panic!("--animate should be followed by terminal, pbm or cast"),
+        }
+    }
+
     writeln!(file, "time for parsing:    {:?}\ntime for puzzle one: {:?}\ntime for puzzle two: {:?}", parse_time, time1, time2)
         .expect("failed to write to times.txt");
 }
@@ -101,22 +98,119 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::{roll_grid, Differential};
 
     const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
+    /// The grid as a matrix of zeros and ones, for the original matrix scan
+    fn parse_matrix(input: &str) -> Vec<Vec<i32>> {
+        input.trim_end()
+            .lines()
+            .map(|line| line.chars().map(|c| (c == ROLL) as i32).collect())
+            .collect()
+    }
+
+    fn grid_from_matrix(matrix: &[Vec<i32>]) -> BitGrid {
+        let col_count = matrix.first().map_or(0, |row| row.len());
+        let mut grid = BitGrid::new(matrix.len(), col_count);
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                grid.set(i, j, cell == 1);
+            }
+        }
+        grid
+    }
+
+    /// The original matrix scan, kept to check the bit grid against
+    fn removable_rolls_reference(matrix: &[Vec<i32>]) -> Vec<Vec<i32>> {
+        let row_count = matrix.len();
+
+        let mut result_matrix = matrix.to_vec();
+
+        result_matrix.iter_mut().enumerate().for_each(|(i, row)| {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let start_k = if i == 0 { 0 } else { i - 1 };
+                let start_l = if j == 0 { 0 } else { j - 1 };
+                let adjacency: i32 = matrix[start_k..(i + 2).min(row_count)]
+                    .iter()
+                    .map(|neighbour_row| {
+                        neighbour_row[start_l..(j + 2).min(neighbour_row.len())].iter().sum::<i32>()
+                    })
+                    .sum();
+
+                if adjacency < 5 && *cell == 1 {
+                    *cell = 1;
+                } else {
+                    *cell = 0;
+                }
+            }
+        });
+
+        result_matrix
+    }
+
     #[test]
     fn test_example_input1() {
         const EXAMPLE_OUTPUT: i32 = 13;
-        let parsed_input = parse_input(EXAMPLE_INPUT);
-        let result = solve_puzzle1(&parsed_input);
+        let grid = parse_input(EXAMPLE_INPUT);
+        let result = solve_puzzle1(&grid);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
 
     #[test]
     fn test_example_input2() {
         const EXAMPLE_OUTPUT: i32 = 43;
-        let parsed_input = parse_input(EXAMPLE_INPUT);
-        let result = solve_puzzle2(&parsed_input);
+        let grid = parse_input(EXAMPLE_INPUT);
+        let result = solve_puzzle2(&grid);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_simulate_removal_matches_brute_force() {
+        let grid = parse_input(EXAMPLE_INPUT);
+        let removal = simulate_removal(&grid);
+        assert_eq!(removal.total_removed() as i32, solve_puzzle2_brute_force(&grid));
+        assert_eq!(removal.rounds[0].len() as i32, solve_puzzle1(&grid));
+        assert_eq!(removal.round_count(), 9);
+    }
+
+    #[test]
+    fn test_bit_grid_matches_matrix_scan() {
+        // a pseudo-random grid wide enough to span several words per row
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let matrix: Vec<Vec<i32>> = (0..37)
+            .map(|_| {
+                (0..203)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        !state.is_multiple_of(3) as i32
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let reference = removable_rolls_reference(&matrix);
+        let grid = grid_from_matrix(&matrix);
+        assert_eq!(grid.removable(), grid_from_matrix(&reference));
+        assert_eq!(solve_puzzle2_brute_force(&grid), solve_puzzle2(&grid));
+    }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        let differential = Differential::new(2_000, 12);
+        differential.assert_agree(
+            "2025 day 4 part 1",
+            roll_grid,
+            |input| removable_rolls_reference(&parse_matrix(input)).iter().flatten().sum::<i32>(),
+            |input| solve_puzzle1(&parse_input(input)),
+        );
+        differential.assert_agree(
+            "2025 day 4 part 2",
+            roll_grid,
+            |input| solve_puzzle2_brute_force(&parse_input(input)),
+            |input| solve_puzzle2(&parse_input(input)),
+        );
+    }
 }
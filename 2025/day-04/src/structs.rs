@@ -0,0 +1,368 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use rayon::prelude::*;
+
+/// The characters of the puzzle input
+pub const ROLL: char = '@';
+pub const EMPTY: char = '.';
+
+/// The result of removing rolls until none are accessible
+#[derive(Debug, PartialEq, Eq)]
+pub struct Removal {
+    /// The positions removed in each round, in the order they were removed
+    pub rounds: Vec<Vec<(usize, usize)>>,
+}
+
+impl Removal {
+    pub fn total_removed(&self) -> usize {
+        self.rounds.iter().map(|round| round.len()).sum()
+    }
+
+    pub fn round_count(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// All removed positions in removal order
+    pub fn order(&self) -> Vec<(usize, usize)> {
+        self.rounds.iter().flatten().copied().collect()
+    }
+
+    /// The grid before any removal and after each round
+    pub fn frames(&self, grid: &BitGrid) -> Vec<BitGrid> {
+        let mut grid = grid.clone();
+        let mut frames = vec![grid.clone()];
+        for round in &self.rounds {
+            for &(i, j) in round {
+                grid.set(i, j, false);
+            }
+            frames.push(grid.clone());
+        }
+        frames
+    }
+}
+
+/// A grid of rolls packed into 64 cells per word, row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    row_count: usize,
+    col_count: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// A row is longer or shorter than the first one
+    RaggedRow { row: usize, len: usize, expected: usize },
+    UnexpectedChar(char),
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::RaggedRow { row, len, expected } => {
+                write!(f, "Row {} has {} cells, but the first row has {}.", row, len, expected)
+            }
+            ParseGridError::UnexpectedChar(c) => write!(f, "Unexpected character \"{}\" in the grid.", c),
+        }
+    }
+}
+
+impl FromStr for BitGrid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.trim_end().lines().collect();
+        let col_count = lines.first().map_or(0, |line| line.chars().count());
+        let mut grid = BitGrid::new(lines.len(), col_count);
+        for (i, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if len != col_count {
+                return Err(ParseGridError::RaggedRow { row: i, len, expected: col_count });
+            }
+            for (j, c) in line.chars().enumerate() {
+                match c {
+                    ROLL => grid.set(i, j, true),
+                    EMPTY => {}
+                    _ => return Err(ParseGridError::UnexpectedChar(c)),
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl fmt::Display for BitGrid {
+    /// Writes the grid in the puzzle format
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.row_count {
+            let row: String = (0..self.col_count)
+                .map(|j| if self.get(i, j) { ROLL } else { EMPTY })
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds a bit plane to a bit-sliced counter, one column per bit
+fn add_plane(counter: &mut [u64; 4], plane: u64) {
+    let mut carry = plane;
+    for bit in counter.iter_mut() {
+        let next_carry = *bit & carry;
+        *bit ^= carry;
+        carry = next_carry;
+    }
+}
+
+impl BitGrid {
+    /// An empty grid
+    pub fn new(row_count: usize, col_count: usize) -> BitGrid {
+        let words_per_row = col_count.div_ceil(64);
+        BitGrid { row_count, col_count, words_per_row, words: vec![0; row_count * words_per_row] }
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        self.words[i * self.words_per_row + j / 64] >> (j % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        let word = &mut self.words[i * self.words_per_row + j / 64];
+        if value {
+            *word |= 1 << (j % 64);
+        } else {
+            *word &= !(1 << (j % 64));
+        }
+    }
+
+    /// The number of rolls in the grid
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn row(&self, i: usize) -> &[u64] {
+        &self.words[i * self.words_per_row..(i + 1) * self.words_per_row]
+    }
+
+    /// The rolls with fewer than four neighbouring rolls.
+    ///
+    /// Every word of a row is combined with its shifted neighbours from the
+    /// rows above, below and beside it, and the eight resulting bit planes are
+    /// summed with a bit-sliced counter, so 64 cells are handled at once.
+    /// Rows are processed in parallel.
+    pub fn removable(&self) -> BitGrid {
+        let mut result = BitGrid::new(self.row_count, self.col_count);
+        if self.words_per_row == 0 {
+            return result;
+        }
+        let empty = vec![0; self.words_per_row];
+
+        result.words
+            .par_chunks_mut(self.words_per_row)
+            .enumerate()
+            .for_each(|(i, out)| {
+                let above = if i == 0 { &empty[..] } else { self.row(i - 1) };
+                let below = if i + 1 == self.row_count { &empty[..] } else { self.row(i + 1) };
+                let row = self.row(i);
+
+                for w in 0..self.words_per_row {
+                    let mut counter = [0u64; 4];
+                    for (neighbour_row, include_centre) in [(above, true), (row, false), (below, true)] {
+                        let word = neighbour_row[w];
+                        let previous = if w == 0 { 0 } else { neighbour_row[w - 1] };
+                        let next = neighbour_row.get(w + 1).copied().unwrap_or(0);
+                        // bit j of these holds the cell in column j - 1 and j + 1
+                        add_plane(&mut counter, word << 1 | previous >> 63);
+                        add_plane(&mut counter, word >> 1 | next << 63);
+                        if include_centre {
+                            add_plane(&mut counter, word);
+                        }
+                    }
+                    // at least four neighbours sets the bit for 4 or 8
+                    out[w] = row[w] & !(counter[2] | counter[3]);
+                }
+            });
+
+        result
+    }
+
+    /// The grid as a plain PBM image, with rolls in black
+    pub fn to_pbm(&self) -> String {
+        let mut pbm = format!("P1\n{} {}\n", self.col_count, self.row_count);
+        for i in 0..self.row_count {
+            let row: Vec<&str> = (0..self.col_count)
+                .map(|j| if self.get(i, j) { "1" } else { "0" })
+                .collect();
+            pbm.push_str(&row.join(" "));
+            pbm.push('\n');
+        }
+        pbm
+    }
+
+    /// Removes every roll set in the other grid
+    pub fn remove(&mut self, other: &BitGrid) {
+        self.words
+            .par_iter_mut()
+            .zip(&other.words)
+            .for_each(|(word, removed)| *word &= !removed);
+    }
+}
+
+/// An asciinema cast (version 2) showing one frame every `frame_seconds`
+pub fn to_cast(frames: &[BitGrid], frame_seconds: f64) -> String {
+    let (width, height) = frames.first().map_or((0, 0), |frame| (frame.col_count, frame.row_count));
+    let mut cast = format!("{{\"version\": 2, \"width\": {}, \"height\": {}}}\n", width, height);
+    for (index, frame) in frames.iter().enumerate() {
+        // clear the screen and move the cursor home before drawing the frame
+        let output = format!("\\u001b[2J\\u001b[H{}", frame.to_string().replace('\n', "\\r\\n"));
+        cast.push_str(&format!("[{:.3}, \"o\", \"{}\"]\n", index as f64 * frame_seconds, output));
+    }
+    cast
+}
+
+fn neighbours(i: usize, j: usize, row_count: usize, col_count: usize) -> impl Iterator<Item = (usize, usize)> {
+    (i.saturating_sub(1)..(i + 2).min(row_count))
+        .flat_map(move |k| (j.saturating_sub(1)..(j + 2).min(col_count)).map(move |l| (k, l)))
+        .filter(move |&(k, l)| (k, l) != (i, j))
+}
+
+/// Removes every roll with fewer than four neighbouring rolls, round by round.
+///
+/// Keeps the number of neighbouring rolls per cell and, after each round,
+/// only re-examines the cells next to a removed roll.
+pub fn simulate_removal(grid: &BitGrid) -> Removal {
+    let (row_count, col_count) = (grid.row_count, grid.col_count);
+
+    let mut present: Vec<Vec<bool>> = (0..row_count)
+        .map(|i| (0..col_count).map(|j| grid.get(i, j)).collect())
+        .collect();
+    let mut counts: Vec<Vec<u8>> = vec![vec![0; col_count]; row_count];
+    for (i, row) in present.iter().enumerate() {
+        for (j, _) in row.iter().enumerate().filter(|(_, &roll)| roll) {
+            for (k, l) in neighbours(i, j, row_count, col_count) {
+                counts[k][l] += 1;
+            }
+        }
+    }
+
+    let mut queued: Vec<Vec<bool>> = vec![vec![false; col_count]; row_count];
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+    for i in 0..row_count {
+        for j in 0..col_count {
+            if present[i][j] && counts[i][j] < 4 {
+                queued[i][j] = true;
+                queue.push_back((i, j));
+            }
+        }
+    }
+
+    let mut rounds: Vec<Vec<(usize, usize)>> = vec![];
+    while !queue.is_empty() {
+        // everything queued so far is removed at the same time
+        let round: Vec<(usize, usize)> = queue.drain(..).collect();
+        for &(i, j) in &round {
+            present[i][j] = false;
+        }
+        for &(i, j) in &round {
+            for (k, l) in neighbours(i, j, row_count, col_count) {
+                counts[k][l] -= 1;
+                if present[k][l] && !queued[k][l] && counts[k][l] < 4 {
+                    queued[k][l] = true;
+                    queue.push_back((k, l));
+                }
+            }
+        }
+        rounds.push(round);
+    }
+
+    Removal { rounds }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_removal() {
+        // a plus shape: the arms go first, then the lonely centre
+        let grid: BitGrid = ".@.\n@@@\n.@.\n".parse().unwrap();
+        let removal = simulate_removal(&grid);
+        assert_eq!(removal.rounds, vec![vec![(0, 1), (1, 0), (1, 2), (2, 1)], vec![(1, 1)]]);
+        assert_eq!(removal.total_removed(), 5);
+        assert_eq!(removal.round_count(), 2);
+        assert_eq!(removal.order()[4], (1, 1));
+    }
+
+    #[test]
+    fn test_simulate_removal_full_block() {
+        // only the corners of a full block have fewer than four neighbours,
+        // and removing them leaves every edge roll with four
+        let grid: BitGrid = "@@@@\n".repeat(4).parse().unwrap();
+        let removal = simulate_removal(&grid);
+        assert_eq!(removal.rounds, vec![vec![(0, 0), (0, 3), (3, 0), (3, 3)]]);
+        assert_eq!(simulate_removal(&BitGrid::new(0, 0)).total_removed(), 0);
+    }
+
+    #[test]
+    fn test_bit_grid_get_set() {
+        let mut grid = BitGrid::new(2, 130);
+        grid.set(1, 64, true);
+        grid.set(0, 129, true);
+        grid.set(0, 0, true);
+        grid.set(0, 0, false);
+        assert!(grid.get(1, 64) && grid.get(0, 129));
+        assert!(!grid.get(0, 0) && !grid.get(1, 63));
+        assert_eq!(grid.count_ones(), 2);
+
+        let grid: BitGrid = ".@@\n@..\n".parse().unwrap();
+        assert_eq!(grid.to_string(), ".@@\n@..\n");
+        assert_eq!(grid.to_pbm(), "P1\n3 2\n0 1 1\n1 0 0\n");
+    }
+
+    #[test]
+    fn test_parse_grid_errors() {
+        assert_eq!(
+            "@@.\n@.\n".parse::<BitGrid>(),
+            Err(ParseGridError::RaggedRow { row: 1, len: 2, expected: 3 })
+        );
+        assert_eq!("@x.\n".parse::<BitGrid>(), Err(ParseGridError::UnexpectedChar('x')));
+        assert_eq!("".parse::<BitGrid>(), Ok(BitGrid::new(0, 0)));
+    }
+
+    #[test]
+    fn test_removal_frames() {
+        let grid: BitGrid = ".@.\n@@@\n.@.\n".parse().unwrap();
+        let frames = simulate_removal(&grid).frames(&grid);
+        let frames: Vec<String> = frames.iter().map(|frame| frame.to_string()).collect();
+        assert_eq!(frames, vec![".@.\n@@@\n.@.\n", "...\n.@.\n...\n", "...\n...\n...\n"]);
+
+        let grid: BitGrid = "@".parse().unwrap();
+        let cast = to_cast(&simulate_removal(&grid).frames(&grid), 0.5);
+        let expected = concat!(
+            "{\"version\": 2, \"width\": 1, \"height\": 1}\n",
+            "[0.000, \"o\", \"\\u001b[2J\\u001b[H@\\r\\n\"]\n",
+            "[0.500, \"o\", \"\\u001b[2J\\u001b[H.\\r\\n\"]\n",
+        );
+        assert_eq!(cast, expected);
+    }
+
+    #[test]
+    fn test_bit_grid_removable_across_words() {
+        // rolls on both sides of the word boundaries count as neighbours
+        let mut grid = BitGrid::new(3, 130);
+        for i in 0..3 {
+            for j in 62..67 {
+                grid.set(i, j, true);
+            }
+        }
+        let removable = grid.removable();
+        let expected: Vec<(usize, usize)> = vec![(0, 62), (0, 66), (2, 62), (2, 66)];
+        let found: Vec<(usize, usize)> = (0..3)
+            .flat_map(|i| (0..130).map(move |j| (i, j)))
+            .filter(|&(i, j)| removable.get(i, j))
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(BitGrid::new(0, 0).removable().count_ones(), 0);
+    }
+}
@@ -1,4 +1,5 @@
 mod structs;
+use crate::structs::*;
 
 use std::fs::File;
 use std::io::Write;
@@ -33,13 +34,14 @@ fn parse_input(input: &str) -> (Vec<[u64; 2]>, Vec<u64>) {
         })
         .collect();
 
-    return (ranges, ids)
+    (ranges, ids)
 }
 
-fn solve_puzzle1(ranges: &Vec<[u64; 2]>, ids: &Vec<u64>) -> u64 {
+/// Reference for part one that checks every id against every range
+fn solve_puzzle1_brute_force(ranges: &[[u64; 2]], ids: &[u64]) -> u64 {
     let mut fresh_counter = 0;
     for id in ids {
-        for range in ranges{
+        for range in ranges {
             if *id >= range[0] && *id <= range[1] {
                 fresh_counter += 1;
                 break
@@ -49,38 +51,34 @@ fn solve_puzzle1(ranges: &Vec<[u64; 2]>, ids: &Vec<u64>) -> u64 {
     fresh_counter
 }
 
-fn solve_puzzle2(ranges: &Vec<[u64; 2]>, _: &Vec<u64>) -> u64 {
-    let mut ranges2 = ranges.clone();
+/// Reference for part two that merges overlapping ranges pair by pair
+fn solve_puzzle2_brute_force(ranges: &[[u64; 2]]) -> u128 {
+    let mut ranges2 = ranges.to_vec();
     let mut i: usize = 0;
-    let mut full_iteration: bool = true;
 
-    loop { // Merge ranges together
-        // println!("i: {}, ranges2.len(): {}", i, ranges2.len());
-        if i+1 >= ranges2.len() {
-            break
-        }
-        for j in i+1..ranges2.len() {
-            // println!("  j: {}, ranges2: {:?}", j, ranges2);
-            full_iteration = true;
-            if ranges2[i][0] <= ranges2[j][1] && ranges2[i][1] >= ranges2[j][0] {
+    // merge ranges together until no two of them overlap
+    while i + 1 < ranges2.len() {
+        let overlapping = (i + 1..ranges2.len())
+            .find(|&j| ranges2[i][0] <= ranges2[j][1] && ranges2[i][1] >= ranges2[j][0]);
+        match overlapping {
+            Some(j) => {
                 ranges2[i][0] = ranges2[i][0].min(ranges2[j][0]);
                 ranges2[i][1] = ranges2[i][1].max(ranges2[j][1]);
                 ranges2.remove(j);
-                full_iteration = false;
-                break
             }
-        }
-        if full_iteration {
-            i += 1;
+            None => i += 1,
         }
     }
 
-    let mut fresh_count = 0;
-    for range in ranges2 {
-        fresh_count += 1 + range[1] - range[0];
-    }
+    ranges2.iter().map(|range| 1 + (range[1] - range[0]) as u128).sum()
+}
 
-    fresh_count
+fn solve_puzzle1(index: &FreshIndex, ids: &[u64]) -> u64 {
+    index.count_fresh(ids) as u64
+}
+
+fn solve_puzzle2(index: &FreshIndex) -> u128 {
+    index.total_fresh()
 }
 
 fn main() {
@@ -91,18 +89,45 @@ fn main() {
 
     let parse_timer = Instant::now();
     let (ranges, ids) = parse_input(INPUT);
+    let index = FreshIndex::from(&ranges[..]);
     let parse_time = parse_timer.elapsed();
 
+    let brute_force = std::env::args().any(|arg| arg == "--brute-force");
+
     let timer1 = Instant::now();
-    let solution1 = solve_puzzle1(&ranges, &ids);
+    let solution1 = if brute_force { solve_puzzle1_brute_force(&ranges, &ids) } else { solve_puzzle1(&index, &ids) };
     let time1 = timer1.elapsed();
     println!("Solution to puzzle one: {}", solution1);
 
     let timer2 = Instant::now();
-    let solution2 = solve_puzzle2(&ranges, &ids);
+    let solution2 = if brute_force { solve_puzzle2_brute_force(&ranges) } else { solve_puzzle2(&index) };
     let time2 = timer2.elapsed();
     println!("Solution to puzzle two: {}", solution2);
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--covering") {
+        let id: u64 = args
+            .get(i + 1)
+            .expect("--covering should be followed by an id")
+            .parse()
+            .expect("failed to parse id as u64");
+        match index.covering_range(id) {
+            Some(range) => println!("{} is fresh, covered by {}-{}", id, range[0], range[1]),
+            None => println!("{} is spoiled", id),
+        }
+    }
+    if args.iter().any(|arg| arg == "--redundant") {
+        for range in index.redundant_ranges() {
+            println!("redundant: {}-{}", range[0], range[1]);
+        }
+    }
+    if args.iter().any(|arg| arg == "--largest-gap") {
+        match index.largest_gap() {
+            Some(gap) => println!("largest gap: {}-{} ({} ids)", gap[0], gap[1], gap[1] - gap[0] + 1),
+            None => println!("no gap between fresh ranges"),
+        }
+    }
+
     writeln!(file, "time for parsing:    {:?}\ntime for puzzle one: {:?}\ntime for puzzle two: {:?}", parse_time, time1, time2)
         .expect("failed to write to times.txt");
 }
@@ -110,6 +135,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::{ingredient_database, Differential};
 
     const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
@@ -117,15 +143,33 @@ mod tests {
     fn test_example_input1() {
         const EXAMPLE_OUTPUT: u64 = 3;
         let (ranges, ids) = parse_input(EXAMPLE_INPUT);
-        let result = solve_puzzle1(&ranges, &ids);
+        let result = solve_puzzle1(&FreshIndex::from(&ranges[..]), &ids);
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
 
     #[test]
     fn test_example_input2() {
-        const EXAMPLE_OUTPUT: u64 = 14;
-        let (ranges, ids) = parse_input(EXAMPLE_INPUT);
-        let result = solve_puzzle2(&ranges, &ids);
+        const EXAMPLE_OUTPUT: u128 = 14;
+        let (ranges, _) = parse_input(EXAMPLE_INPUT);
+        let result = solve_puzzle2(&FreshIndex::from(&ranges[..]));
         assert_eq!(result, EXAMPLE_OUTPUT);
     }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        let differential = Differential::new(2_000, 20);
+        differential.assert_agree(
+            "2025 day 5",
+            ingredient_database,
+            |input| {
+                let (ranges, ids) = parse_input(input);
+                (solve_puzzle1_brute_force(&ranges, &ids), solve_puzzle2_brute_force(&ranges))
+            },
+            |input| {
+                let (ranges, ids) = parse_input(input);
+                let index = FreshIndex::from(&ranges[..]);
+                (solve_puzzle1(&index, &ids), solve_puzzle2(&index))
+            },
+        );
+    }
 }
@@ -0,0 +1,135 @@
+/// The fresh ingredient ranges, sorted and merged for fast lookups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreshIndex {
+    /// The ranges as listed in the database
+    ranges: Vec<[u64; 2]>,
+    /// Disjoint, non-adjacent ranges covering the same ids, sorted by start
+    merged: Vec<[u64; 2]>,
+}
+
+impl From<&[[u64; 2]]> for FreshIndex {
+    fn from(ranges: &[[u64; 2]]) -> Self {
+        let mut sorted = ranges.to_vec();
+        sorted.sort_unstable();
+
+        let mut merged: Vec<[u64; 2]> = vec![];
+        for range in sorted {
+            match merged.last_mut() {
+                // overlapping or directly adjacent ranges become one
+                Some(last) if range[0] <= last[1].saturating_add(1) => last[1] = last[1].max(range[1]),
+                _ => merged.push(range),
+            }
+        }
+
+        FreshIndex { ranges: ranges.to_vec(), merged }
+    }
+}
+
+impl FreshIndex {
+    /// The merged range containing the id, if any
+    pub fn covering_range(&self, id: u64) -> Option<[u64; 2]> {
+        let after = self.merged.partition_point(|range| range[0] <= id);
+        after
+            .checked_sub(1)
+            .map(|i| self.merged[i])
+            .filter(|range| id <= range[1])
+    }
+
+    pub fn is_fresh(&self, id: u64) -> bool {
+        self.covering_range(id).is_some()
+    }
+
+    pub fn count_fresh(&self, ids: &[u64]) -> usize {
+        ids.iter().filter(|&&id| self.is_fresh(id)).count()
+    }
+
+    /// The number of ids in any of the ranges, which for the full
+    /// range of ids is one more than fits into a u64
+    pub fn total_fresh(&self) -> u128 {
+        self.merged.iter().map(|range| (range[1] - range[0]) as u128 + 1).sum()
+    }
+
+    /// The ranges of the database that could be dropped one at a time
+    /// without changing which ids are fresh
+    pub fn redundant_ranges(&self) -> Vec<[u64; 2]> {
+        // sweep over the ranges to find the stretches covered by exactly one of them
+        let mut events: Vec<(u64, i64)> = vec![];
+        for range in &self.ranges {
+            events.push((range[0], 1));
+            if let Some(end) = range[1].checked_add(1) {
+                events.push((end, -1));
+            }
+        }
+        events.sort_unstable();
+
+        let mut covered_once: Vec<[u64; 2]> = vec![];
+        let mut depth = 0;
+        for (i, &(position, change)) in events.iter().enumerate() {
+            depth += change;
+            let next = events.get(i + 1).map_or(Some(u64::MAX), |&(next, _)| next.checked_sub(1));
+            if let Some(next) = next {
+                if depth == 1 && position <= next {
+                    covered_once.push([position, next]);
+                }
+            }
+        }
+
+        // a range is needed if one of those stretches overlaps it
+        self.ranges
+            .iter()
+            .filter(|range| {
+                let after = covered_once.partition_point(|stretch| stretch[0] <= range[1]);
+                after == 0 || covered_once[after - 1][1] < range[0]
+            })
+            .copied()
+            .collect()
+    }
+
+    /// The longest run of spoiled ids between two fresh ranges, as an inclusive range
+    pub fn largest_gap(&self) -> Option<[u64; 2]> {
+        self.merged
+            .windows(2)
+            .map(|pair| [pair[0][1] + 1, pair[1][0] - 1])
+            .max_by_key(|gap| (gap[1] - gap[0], std::cmp::Reverse(gap[0])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_index_queries() {
+        let index = FreshIndex::from(&[[3, 5], [10, 14], [16, 20], [12, 18]][..]);
+        assert_eq!(index.merged, vec![[3, 5], [10, 20]]);
+        assert_eq!(index.covering_range(17), Some([10, 20]));
+        assert_eq!(index.covering_range(2), None);
+        assert_eq!(index.covering_range(8), None);
+        assert_eq!(index.covering_range(21), None);
+        assert!(index.is_fresh(3) && index.is_fresh(5) && !index.is_fresh(9));
+        assert_eq!(index.count_fresh(&[1, 5, 8, 11, 17, 32]), 3);
+        assert_eq!(index.total_fresh(), 14);
+        assert_eq!(index.largest_gap(), Some([6, 9]));
+    }
+
+    #[test]
+    fn test_fresh_index_adjacent_and_extreme_ranges() {
+        let index = FreshIndex::from(&[[6, 8], [3, 5], [u64::MAX - 1, u64::MAX]][..]);
+        assert_eq!(index.merged, vec![[3, 8], [u64::MAX - 1, u64::MAX]]);
+        assert!(index.is_fresh(u64::MAX));
+        assert_eq!(index.largest_gap(), Some([9, u64::MAX - 2]));
+        assert_eq!(FreshIndex::from(&[][..]).largest_gap(), None);
+        assert_eq!(FreshIndex::from(&[[0, u64::MAX], [5, 7]][..]).total_fresh(), u64::MAX as u128 + 1);
+    }
+
+    #[test]
+    fn test_redundant_ranges() {
+        // [12, 14] lies within [10, 16], and [15, 20] is covered by [10, 16] and [17, 25] together
+        let index = FreshIndex::from(&[[10, 16], [12, 14], [15, 20], [17, 25], [30, 30]][..]);
+        assert_eq!(index.redundant_ranges(), vec![[12, 14], [15, 20]]);
+
+        // each copy of a duplicate could go, but not both
+        let index = FreshIndex::from(&[[1, 4], [1, 4], [4, u64::MAX]][..]);
+        assert_eq!(index.redundant_ranges(), vec![[1, 4], [1, 4]]);
+    }
+}
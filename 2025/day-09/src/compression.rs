@@ -0,0 +1,98 @@
+/// Maps the distinct values of one axis onto a compact range of cells.
+///
+/// Cell 2 * i stands for the i-th smallest value and cell 2 * i + 1 for the
+/// values strictly between it and the next one, which may be none at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    values: Vec<u64>,
+}
+
+impl Compression {
+    pub fn new(values: impl IntoIterator<Item = u64>) -> Compression {
+        let mut values: Vec<u64> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        Compression { values }
+    }
+
+    pub fn cell_count(&self) -> usize {
+        (2 * self.values.len()).saturating_sub(1)
+    }
+
+    /// The cell of a value that was compressed
+    pub fn cell(&self, value: u64) -> usize {
+        2 * self.values.binary_search(&value).expect("value should be part of the compression")
+    }
+
+    /// How many values a cell stands for
+    pub fn cell_width(&self, cell: usize) -> u64 {
+        if cell.is_multiple_of(2) {
+            1
+        } else {
+            self.values[cell / 2 + 1] - self.values[cell / 2] - 1
+        }
+    }
+}
+
+/// Sums over rectangles of a 2D table in constant time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSums {
+    col_count: usize,
+    sums: Vec<u64>,
+}
+
+impl PrefixSums {
+    pub fn new(table: &[Vec<u64>]) -> PrefixSums {
+        let col_count = table.first().map_or(0, |row| row.len());
+        let mut sums = vec![0; (table.len() + 1) * (col_count + 1)];
+        for (i, row) in table.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                sums[(i + 1) * (col_count + 1) + j + 1] = value
+                    + sums[i * (col_count + 1) + j + 1]
+                    + sums[(i + 1) * (col_count + 1) + j]
+                    - sums[i * (col_count + 1) + j];
+            }
+        }
+        PrefixSums { col_count, sums }
+    }
+
+    fn at(&self, i: usize, j: usize) -> u64 {
+        self.sums[i * (self.col_count + 1) + j]
+    }
+
+    /// The sum over rows low[0]..=high[0] and columns low[1]..=high[1]
+    pub fn sum(&self, low: [usize; 2], high: [usize; 2]) -> u64 {
+        self.at(high[0] + 1, high[1] + 1) + self.at(low[0], low[1])
+            - self.at(low[0], high[1] + 1)
+            - self.at(high[0] + 1, low[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression() {
+        let compression = Compression::new([100, 7, 8, 7, 1_000_000]);
+        assert_eq!(compression.cell_count(), 7);
+        assert_eq!(compression.cell(7), 0);
+        assert_eq!(compression.cell(100), 4);
+        assert_eq!(compression.cell(1_000_000), 6);
+        let widths: Vec<u64> = (0..7).map(|cell| compression.cell_width(cell)).collect();
+        assert_eq!(widths, vec![1, 0, 1, 91, 1, 999_899, 1]);
+        assert_eq!(Compression::new([]).cell_count(), 0);
+    }
+
+    #[test]
+    fn test_prefix_sums() {
+        let table = vec![
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+        ];
+        let sums = PrefixSums::new(&table);
+        assert_eq!(sums.sum([0, 0], [1, 2]), 21);
+        assert_eq!(sums.sum([1, 1], [1, 2]), 11);
+        assert_eq!(sums.sum([0, 1], [0, 1]), 2);
+    }
+}
@@ -1,4 +1,6 @@
+mod compression;
 mod structs;
+use crate::structs::*;
 
 use std::fs::File;
 use std::io::Write;
@@ -21,53 +23,57 @@ fn parse_input(input: &str) -> Vec<[u64;2]> {
         .collect()
 }
 
-fn solve_puzzle1(coords: &Vec<[u64;2]>) -> u64 {
+/// Reference for part one that compares every pair of red tiles
+fn solve_puzzle1_brute_force(coords: &[[u64;2]]) -> u64 {
     coords
         .par_iter()
         .enumerate()
-        .map(|(i, coord)| {
-            let mut area: u64 = 0;
-            for j in i..coords.len() {
-                let width: u64 = if coord[0] > coords[j][0] { 1 + coord[0] - coords[j][0] } else { 1 + coords[j][0] - coord[0] };
-                let height: u64 = if coord[1] > coords[j][1] { 1 + coord[1] - coords[j][1] } else { 1 + coords[j][1] - coord[1] };
-                area = area.max(width * height);
-            }
-            area
+        .map(|(i, &coord)| {
+            coords[i..]
+                .iter()
+                .map(|&other| area(coord, other))
+                .max()
+                .unwrap_or(0)
         })
         .reduce(|| 0, |a, b| a.max(b))
 }
 
-fn solve_puzzle2(coords: &Vec<[u64;2]>) -> u64 {
-    coords
+fn solve_puzzle1(coords: &[[u64;2]]) -> Rectangle {
+    largest_rectangle(coords).expect("there should be at least one red tile")
+}
+
+/// Reference for part two that checks the rectangles tile by tile, largest first
+fn solve_puzzle2_brute_force(coords: &[[u64;2]]) -> u64 {
+    let polygon = Polygon::new(coords.to_vec());
+    let mut pairs: Vec<(u64, [u64;2], [u64;2])> = coords
         .iter()
         .enumerate()
-        .map(|(i, coord)| {
-            println!("i: {}", i);
-            let mut area: u64 = u64::MAX;
-            for j in 0..coords.len() {
-                if coord[0] > coords[j][0] || coord[1] > coords[j][1] { continue }
-                    let width: u64 = 1 + coords[j][0] - coord[0];
-                    let height: u64 = 1 + coords[j][1] - coord[1];
-                if width > 1 && height > 1 {
-                    area = area.min(width * height);
-                }
-                println!("  j: {}, area: {}", j, area);
-            }
-            if area == u64::MAX {
-                area = 0;
-                for j in 0..coords.len() {
-                if coord[0] > coords[j][0] || coord[1] > coords[j][1] { continue }
-                    let width: u64 = 1 + coords[j][0] - coord[0];
-                    let height: u64 = 1 + coords[j][1] - coord[1];
-                    area = area.max(width * height);
-                    println!("  j: {}, area: {}", j, area);
-                }
-                return area
-            }
-            area
+        .flat_map(|(i, &coord)| coords[i..].iter().map(move |&other| (area(coord, other), coord, other)))
+        .collect();
+    pairs.par_sort_unstable_by_key(|&(area, _, _)| std::cmp::Reverse(area));
+    pairs
+        .par_iter()
+        .find_first(|&&(_, coord, other)| polygon.contains_rectangle(coord, other))
+        .map_or(0, |&(area, _, _)| area)
+}
+
+/// Checks every pair of red tiles as opposite corners of a rectangle
+/// that has to lie within the loop
+fn solve_puzzle2(coords: &[[u64;2]]) -> Rectangle {
+    let polygon = FilledPolygon::from(&Polygon::new(coords.to_vec()));
+    coords
+        .par_iter()
+        .enumerate()
+        .map(|(i, &coord)| {
+            coords[i..]
+                .iter()
+                .filter(|&&other| polygon.contains_rectangle(coord, other))
+                .map(|&other| Rectangle { area: area(coord, other), corners: [coord, other] })
+                .max_by_key(|rectangle| rectangle.area)
         })
-        .reduce( |a, b| a.max(b))
-        .unwrap()
+        .flatten()
+        .max_by_key(|rectangle| rectangle.area)
+        .expect("there should be at least one red tile")
 }
 
 fn main() {
@@ -80,16 +86,41 @@ fn main() {
     let parsed_input = parse_input(INPUT);
     let parse_time = parse_timer.elapsed();
 
+    let brute_force = std::env::args().any(|arg| arg == "--brute-force");
+
     let timer1 = Instant::now();
-    let solution1 = solve_puzzle1(&parsed_input);
+    let solution1 = if brute_force {
+        solve_puzzle1_brute_force(&parsed_input)
+    } else {
+        let rectangle = solve_puzzle1(&parsed_input);
+        println!("Largest rectangle between {:?} and {:?}", rectangle.corners[0], rectangle.corners[1]);
+        rectangle.area
+    };
     let time1 = timer1.elapsed();
     println!("Solution to puzzle one: {}", solution1);
 
     let timer2 = Instant::now();
-    let solution2 = solve_puzzle2(&parsed_input);
+    let solution2 = if brute_force {
+        solve_puzzle2_brute_force(&parsed_input)
+    } else {
+        let rectangle = solve_puzzle2(&parsed_input);
+        println!("Largest rectangle inside the loop between {:?} and {:?}", rectangle.corners[0], rectangle.corners[1]);
+        rectangle.area
+    };
     let time2 = timer2.elapsed();
     println!("Solution to puzzle two: {}", solution2);
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--svg") {
+        let path = args.get(i + 1).expect("--svg should be followed by a file name");
+        let polygon = Polygon::new(parsed_input.clone());
+        let rectangles = [
+            (solve_puzzle1(&parsed_input), "blue"),
+            (solve_puzzle2(&parsed_input), "orange"),
+        ];
+        std::fs::write(path, polygon.to_svg(&rectangles, 800.0)).expect("failed to write svg file");
+    }
+
     writeln!(file, "time for parsing:    {:?}\ntime for puzzle one: {:?}\ntime for puzzle two: {:?}", parse_time, time1, time2)
         .expect("failed to write to times.txt");
 }
@@ -97,6 +128,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::{tile_loop, Differential};
 
     const EXAMPLE_INPUT: &str = include_str!("../data/example_input.txt");
 
@@ -105,7 +137,8 @@ mod tests {
         const EXAMPLE_OUTPUT: u64 = 50;
         let parsed_input = parse_input(EXAMPLE_INPUT);
         let result = solve_puzzle1(&parsed_input);
-        assert_eq!(result, EXAMPLE_OUTPUT);
+        assert_eq!(result.area, EXAMPLE_OUTPUT);
+        assert_eq!(area(result.corners[0], result.corners[1]), EXAMPLE_OUTPUT);
     }
 
     #[test]
@@ -113,6 +146,50 @@ mod tests {
         const EXAMPLE_OUTPUT: u64 = 24;
         let parsed_input = parse_input(EXAMPLE_INPUT);
         let result = solve_puzzle2(&parsed_input);
-        assert_eq!(result, EXAMPLE_OUTPUT);
+        assert_eq!(result.area, EXAMPLE_OUTPUT);
+    }
+
+    #[test]
+    fn test_example_input2_brute_force() {
+        let parsed_input = parse_input(EXAMPLE_INPUT);
+        assert_eq!(solve_puzzle2_brute_force(&parsed_input), solve_puzzle2(&parsed_input).area);
+    }
+
+    #[test]
+    fn test_solve_puzzle1_matches_brute_force() {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        for size in 1..200 {
+            let coords: Vec<[u64;2]> = (0..size)
+                .map(|_| {
+                    let mut next = || {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        state % 1_000
+                    };
+                    [next(), next()]
+                })
+                .collect();
+            let rectangle = solve_puzzle1(&coords);
+            assert_eq!(rectangle.area, solve_puzzle1_brute_force(&coords));
+            assert!(rectangle.corners.iter().all(|corner| coords.contains(corner)));
+        }
+    }
+
+    #[test]
+    fn test_differential_against_brute_force() {
+        let differential = Differential::new(1_000, 48);
+        differential.assert_agree(
+            "2025 day 9",
+            tile_loop,
+            |input| {
+                let coords = parse_input(input);
+                (solve_puzzle1_brute_force(&coords), solve_puzzle2_brute_force(&coords))
+            },
+            |input| {
+                let coords = parse_input(input);
+                (solve_puzzle1(&coords).area, solve_puzzle2(&coords).area)
+            },
+        );
     }
 }
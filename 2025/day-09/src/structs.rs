@@ -0,0 +1,422 @@
+use crate::compression::{Compression, PrefixSums};
+
+/// A closed loop of red tiles where consecutive tiles share a row or a column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<[u64; 2]>,
+}
+
+/// The loop drawn on a compressed grid, with its outside filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilledPolygon {
+    xs: Compression,
+    ys: Compression,
+    /// Counts the tiles outside the loop per compressed cell
+    outside: PrefixSums,
+}
+
+/// A rectangle between two red tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub area: u64,
+    pub corners: [[u64; 2]; 2],
+}
+
+/// The number of tiles in the rectangle spanned by two opposite corners
+pub fn area(a: [u64; 2], b: [u64; 2]) -> u64 {
+    (a[0].abs_diff(b[0]) + 1) * (a[1].abs_diff(b[1]) + 1)
+}
+
+/// The points not dominated by any other point towards the given corner.
+///
+/// Without flips that is the lower left corner, and any point further down and
+/// to the left would make a bigger rectangle with the same opposite corner.
+fn staircase(points: &[[u64; 2]], flip: [bool; 2]) -> Vec<[u64; 2]> {
+    let key = |point: &[u64; 2]| {
+        let x = if flip[0] { u64::MAX - point[0] } else { point[0] };
+        let y = if flip[1] { u64::MAX - point[1] } else { point[1] };
+        (x, y)
+    };
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable_by_key(key);
+
+    let mut lowest = u64::MAX;
+    let mut front = vec![];
+    for point in sorted {
+        let (_, y) = key(&point);
+        if front.is_empty() || y < lowest {
+            lowest = y;
+            front.push(point);
+        }
+    }
+    front
+}
+
+/// The largest rectangle with red tiles in two opposite corners.
+///
+/// Only pairs from opposite staircases can be the largest. The lower left
+/// staircase is searched against the upper right one, and the upper left one
+/// against the lower right one with the y axis mirrored.
+pub fn largest_rectangle(points: &[[u64; 2]]) -> Option<Rectangle> {
+    [false, true]
+        .into_iter()
+        .filter_map(|mirror| {
+            let front = staircase(points, [false, mirror]);
+            let mut opposite = staircase(points, [true, !mirror]);
+            opposite.reverse();
+            let mirrored = |point: [u64; 2]| {
+                let y = if mirror { -(point[1] as i128) } else { point[1] as i128 };
+                [point[0] as i128, y]
+            };
+            // negative when the second point is not above and to the right of the first,
+            // and never more than the area of the two points either way
+            let signed_area = |i: usize, j: usize| {
+                let (a, b) = (mirrored(front[i]), mirrored(opposite[j]));
+                (b[0] - a[0] + 1) * (b[1] - a[1] + 1)
+            };
+            let mut best = None;
+            best_partners(0..front.len(), 0..opposite.len(), &signed_area, &mut best);
+            best.map(|(_, i, j)| Rectangle { area: area(front[i], opposite[j]), corners: [front[i], opposite[j]] })
+        })
+        .max_by_key(|rectangle| rectangle.area)
+}
+
+/// Finds the pair with the largest signed area between two staircases that
+/// both run from the upper left to the lower right.
+///
+/// Moving down the first staircase never moves the best partner back up the
+/// second one, so the best partner of the middle point splits the search in two.
+fn best_partners(
+    rows: std::ops::Range<usize>,
+    cols: std::ops::Range<usize>,
+    signed_area: &impl Fn(usize, usize) -> i128,
+    best: &mut Option<(i128, usize, usize)>,
+) {
+    if rows.is_empty() || cols.is_empty() {
+        return;
+    }
+    let mid = rows.start + rows.len() / 2;
+    let (value, partner) = cols
+        .clone()
+        .map(|j| (signed_area(mid, j), j))
+        .fold((i128::MIN, cols.start), |max, next| if next.0 > max.0 { next } else { max });
+    if best.is_none_or(|(best_value, _, _)| value > best_value) {
+        *best = Some((value, mid, partner));
+    }
+    best_partners(rows.start..mid, cols.start..partner + 1, signed_area, best);
+    best_partners(mid + 1..rows.end, partner..cols.end, signed_area, best);
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<[u64; 2]>) -> Polygon {
+        for (i, a) in vertices.iter().enumerate() {
+            let b = vertices[(i + 1) % vertices.len()];
+            assert!(a[0] == b[0] || a[1] == b[1], "consecutive tiles should share a row or a column");
+        }
+        Polygon { vertices }
+    }
+
+    /// All edges as pairs of (lower, upper) corners
+    fn edges(&self) -> impl Iterator<Item = ([u64; 2], [u64; 2])> + '_ {
+        self.vertices.iter().enumerate().map(|(i, &a)| {
+            let b = self.vertices[(i + 1) % self.vertices.len()];
+            ([a[0].min(b[0]), a[1].min(b[1])], [a[0].max(b[0]), a[1].max(b[1])])
+        })
+    }
+
+    /// Whether a tile is red or green, meaning it lies on the loop or inside it
+    fn contains_tile(&self, tile: [u64; 2]) -> bool {
+        let on_boundary = self.edges().any(|(low, high)| {
+            (low[0]..=high[0]).contains(&tile[0]) && (low[1]..=high[1]).contains(&tile[1])
+        });
+        if on_boundary {
+            return true;
+        }
+
+        // cast a ray towards increasing x and count the vertical edges it crosses,
+        // each edge covering its lower end but not its upper one
+        let crossings = self
+            .edges()
+            .filter(|(low, high)| low[0] == high[0] && low[0] > tile[0])
+            .filter(|(low, high)| (low[1]..high[1]).contains(&tile[1]))
+            .count();
+        crossings % 2 == 1
+    }
+
+    /// One tile for every stretch of low..=high in which no vertex coordinate
+    /// of the axis changes whether tiles are inside
+    fn representatives(&self, axis: usize, low: u64, high: u64) -> Vec<u64> {
+        let mut stops: Vec<u64> = self
+            .vertices
+            .iter()
+            .map(|vertex| vertex[axis])
+            .filter(|&v| v > low && v < high)
+            .chain([low, high])
+            .collect();
+        stops.sort_unstable();
+        stops.dedup();
+
+        let gaps: Vec<u64> = stops
+            .windows(2)
+            .filter(|pair| pair[1] - pair[0] > 1)
+            .map(|pair| pair[0] + 1)
+            .collect();
+        stops.extend(gaps);
+        stops
+    }
+
+    /// Whether every tile of the rectangle spanned by two corners is red or green.
+    ///
+    /// Tiles between the same vertex coordinates on both axes are all inside or
+    /// all outside, so one tile of each such block is enough to check.
+    pub fn contains_rectangle(&self, a: [u64; 2], b: [u64; 2]) -> bool {
+        let low = [a[0].min(b[0]), a[1].min(b[1])];
+        let high = [a[0].max(b[0]), a[1].max(b[1])];
+        let xs = self.representatives(0, low[0], high[0]);
+        let ys = self.representatives(1, low[1], high[1]);
+
+        // most rectangles that stick out already do so along their sides
+        let sides = xs.iter().all(|&x| self.contains_tile([x, low[1]]) && self.contains_tile([x, high[1]]))
+            && ys.iter().all(|&y| self.contains_tile([low[0], y]) && self.contains_tile([high[0], y]));
+        sides && xs.iter().all(|&x| ys.iter().all(|&y| self.contains_tile([x, y])))
+    }
+}
+
+impl Polygon {
+    /// Draws the loop, its red tiles and the given rectangles with their colours,
+    /// scaled so that the longer side of the loop is `size` pixels
+    pub fn to_svg(&self, rectangles: &[(Rectangle, &str)], size: f64) -> String {
+        const MARGIN: f64 = 10.0;
+        let min = |axis: usize| self.vertices.iter().map(|vertex| vertex[axis]).min().unwrap_or(0);
+        let max = |axis: usize| self.vertices.iter().map(|vertex| vertex[axis]).max().unwrap_or(0);
+        let (min_x, min_y) = (min(0), min(1));
+        let span = (max(0) - min_x).max(max(1) - min_y).max(1);
+        let scale = size / span as f64;
+        let x = |value: u64| MARGIN + (value - min_x) as f64 * scale;
+        let y = |value: u64| MARGIN + (value - min_y) as f64 * scale;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\">\n",
+            x(max(0)) + MARGIN,
+            y(max(1)) + MARGIN,
+        );
+        let points: Vec<String> = self.vertices
+            .iter()
+            .map(|vertex| format!("{:.2},{:.2}", x(vertex[0]), y(vertex[1])))
+            .collect();
+        svg.push_str(&format!(
+            "<polygon points=\"{}\" fill=\"#c8e6c9\" stroke=\"green\" stroke-width=\"1\"/>\n",
+            points.join(" ")
+        ));
+        for vertex in &self.vertices {
+            svg.push_str(&format!(
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"2\" fill=\"red\"/>\n",
+                x(vertex[0]), y(vertex[1])
+            ));
+        }
+        for (rectangle, colour) in rectangles {
+            let [a, b] = rectangle.corners;
+            svg.push_str(&format!(
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                x(a[0].min(b[0])), y(a[1].min(b[1])),
+                a[0].abs_diff(b[0]) as f64 * scale, a[1].abs_diff(b[1]) as f64 * scale,
+                colour
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl From<&Polygon> for FilledPolygon {
+    /// Draws the edges on the compressed grid and flood-fills the outside from
+    /// a border of padding cells, so whatever is left is on or inside the loop
+    fn from(polygon: &Polygon) -> Self {
+        let xs = Compression::new(polygon.vertices.iter().map(|vertex| vertex[0]));
+        let ys = Compression::new(polygon.vertices.iter().map(|vertex| vertex[1]));
+        let col_count = xs.cell_count() + 2;
+        let row_count = ys.cell_count() + 2;
+
+        let mut boundary = vec![vec![false; col_count]; row_count];
+        for (low, high) in polygon.edges() {
+            for row in &mut boundary[ys.cell(low[1]) + 1..=ys.cell(high[1]) + 1] {
+                for cell in &mut row[xs.cell(low[0]) + 1..=xs.cell(high[0]) + 1] {
+                    *cell = true;
+                }
+            }
+        }
+
+        let mut reached = vec![vec![false; col_count]; row_count];
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+        reached[0][0] = true;
+        while let Some((i, j)) = stack.pop() {
+            let next = [
+                (i.wrapping_sub(1), j),
+                (i + 1, j),
+                (i, j.wrapping_sub(1)),
+                (i, j + 1),
+            ];
+            for (k, l) in next {
+                if k < row_count && l < col_count && !boundary[k][l] && !reached[k][l] {
+                    reached[k][l] = true;
+                    stack.push((k, l));
+                }
+            }
+        }
+
+        let outside: Vec<Vec<u64>> = (1..row_count - 1)
+            .map(|i| {
+                (1..col_count - 1)
+                    .map(|j| if reached[i][j] { xs.cell_width(j - 1) * ys.cell_width(i - 1) } else { 0 })
+                    .collect()
+            })
+            .collect();
+
+        FilledPolygon { xs, ys, outside: PrefixSums::new(&outside) }
+    }
+}
+
+impl FilledPolygon {
+    /// Whether every tile of the rectangle spanned by two red tiles is red or green
+    pub fn contains_rectangle(&self, a: [u64; 2], b: [u64; 2]) -> bool {
+        let (x1, x2) = (self.xs.cell(a[0]), self.xs.cell(b[0]));
+        let (y1, y2) = (self.ys.cell(a[1]), self.ys.cell(b[1]));
+        self.outside.sum([y1.min(y2), x1.min(x2)], [y1.max(y2), x1.max(x2)]) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A U shape opening upwards, with the notch between x = 3 and x = 6 above y = 3
+    fn u_shape() -> Polygon {
+        Polygon::new(vec![[0, 0], [9, 0], [9, 9], [6, 9], [6, 3], [3, 3], [3, 9], [0, 9]])
+    }
+
+    #[test]
+    fn test_contains_rectangle_concave() {
+        let polygon = u_shape();
+        assert!(polygon.contains_rectangle([0, 0], [9, 3]));
+        assert!(polygon.contains_rectangle([0, 9], [3, 0]));
+        assert!(polygon.contains_rectangle([6, 3], [9, 9]));
+        // spanning the notch
+        assert!(!polygon.contains_rectangle([0, 9], [9, 0]));
+        assert!(!polygon.contains_rectangle([3, 9], [6, 9]));
+        assert!(!polygon.contains_rectangle([0, 9], [6, 3]));
+        // along the bottom of the notch, which is boundary
+        assert!(polygon.contains_rectangle([3, 3], [6, 3]));
+    }
+
+    #[test]
+    fn test_contains_rectangle_adjacent_edges() {
+        // the notch between x = 3 and x = 4 holds no tiles, so spanning it leaves nothing out
+        let polygon = Polygon::new(vec![[0, 0], [9, 0], [9, 9], [4, 9], [4, 3], [3, 3], [3, 9], [0, 9]]);
+        assert!(polygon.contains_rectangle([0, 9], [9, 0]));
+        assert!(polygon.contains_rectangle([3, 9], [4, 3]));
+
+        // one column wider and the tiles at x = 4 above the notch floor are missing
+        let polygon = Polygon::new(vec![[0, 0], [9, 0], [9, 9], [5, 9], [5, 3], [3, 3], [3, 9], [0, 9]]);
+        assert!(!polygon.contains_rectangle([0, 9], [9, 0]));
+        assert!(polygon.contains_rectangle([0, 3], [9, 0]));
+    }
+
+    #[test]
+    fn test_contains_rectangle_outside_corner() {
+        // an L shape: the missing corner lies between two red tiles
+        let polygon = Polygon::new(vec![[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]]);
+        assert!(!polygon.contains_rectangle([4, 0], [0, 4]));
+        assert!(!polygon.contains_rectangle([4, 2], [2, 4]));
+        assert!(polygon.contains_rectangle([4, 2], [0, 0]));
+        assert!(polygon.contains_rectangle([2, 4], [2, 0]));
+    }
+
+    #[test]
+    fn test_filled_polygon_matches_polygon() {
+        let polygons = [
+            u_shape(),
+            Polygon::new(vec![[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]]),
+            // a staircase with long edges
+            Polygon::new(vec![[0, 0], [1_000, 0], [1_000, 10], [500, 10], [500, 700], [20, 700], [20, 90_000], [0, 90_000]]),
+        ];
+        for polygon in polygons {
+            let filled = FilledPolygon::from(&polygon);
+            for &a in &polygon.vertices {
+                for &b in &polygon.vertices {
+                    assert_eq!(filled.contains_rectangle(a, b), polygon.contains_rectangle(a, b), "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_filled_polygon_narrow_notch() {
+        // the notch between x = 3 and x = 4 holds no tiles, so nothing is missing
+        let polygon = Polygon::new(vec![[0, 0], [9, 0], [9, 9], [4, 9], [4, 3], [3, 3], [3, 9], [0, 9]]);
+        let filled = FilledPolygon::from(&polygon);
+        assert!(filled.contains_rectangle([0, 9], [9, 0]));
+        for &a in &polygon.vertices {
+            for &b in &polygon.vertices {
+                assert_eq!(filled.contains_rectangle(a, b), polygon.contains_rectangle(a, b), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_largest_rectangle() {
+        let points = [[7, 1], [11, 1], [11, 7], [9, 7], [9, 5], [2, 5], [2, 3], [7, 3]];
+        let rectangle = largest_rectangle(&points).unwrap();
+        assert_eq!(rectangle.area, 50);
+        assert_eq!(area(rectangle.corners[0], rectangle.corners[1]), 50);
+        assert_eq!(largest_rectangle(&[]), None);
+        assert_eq!(largest_rectangle(&[[3, 4]]).unwrap().area, 1);
+    }
+
+    #[test]
+    fn test_largest_rectangle_all_points_on_front() {
+        // every point of an anti-diagonal is on both staircases
+        let n = 100_000;
+        let points: Vec<[u64; 2]> = (0..n).map(|i| [i, n - 1 - i]).collect();
+        let rectangle = largest_rectangle(&points).unwrap();
+        assert_eq!(rectangle.area, n * n);
+
+        // on a quarter circle the best pair lies somewhere in the middle
+        let points: Vec<[u64; 2]> = (0..=300)
+            .map(|i| {
+                let angle = std::f64::consts::FRAC_PI_2 * i as f64 / 300.0;
+                [(1_000.0 * angle.cos()) as u64, (1_000.0 * angle.sin()) as u64]
+            })
+            .collect();
+        let brute_force = points
+            .iter()
+            .flat_map(|&a| points.iter().map(move |&b| area(a, b)))
+            .max();
+        assert_eq!(largest_rectangle(&points).map(|rectangle| rectangle.area), brute_force);
+    }
+
+    #[test]
+    fn test_staircase() {
+        let points = [[0, 5], [1, 1], [2, 3], [3, 0], [3, 2], [5, 5]];
+        assert_eq!(staircase(&points, [false, false]), vec![[0, 5], [1, 1], [3, 0]]);
+        assert_eq!(staircase(&points, [true, true]), vec![[5, 5]]);
+        assert_eq!(staircase(&points, [false, true]), vec![[0, 5]]);
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let polygon = Polygon::new(vec![[1_000, 2_000], [3_000, 2_000], [3_000, 3_000], [1_000, 3_000]]);
+        let rectangle = Rectangle { area: area([3_000, 3_000], [1_000, 2_000]), corners: [[3_000, 3_000], [1_000, 2_000]] };
+        let svg = polygon.to_svg(&[(rectangle, "blue")], 100.0);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"120\" height=\"70\">"));
+        assert!(svg.contains("points=\"10.00,10.00 110.00,10.00 110.00,60.00 10.00,60.00\""));
+        assert!(svg.contains("<circle cx=\"110.00\" cy=\"60.00\""));
+        assert!(svg.contains("<rect x=\"10.00\" y=\"10.00\" width=\"100.00\" height=\"50.00\" fill=\"none\" stroke=\"blue\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_area() {
+        assert_eq!(area([2, 5], [11, 1]), 50);
+        assert_eq!(area([7, 3], [7, 3]), 1);
+    }
+}
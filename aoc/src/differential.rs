@@ -0,0 +1,207 @@
+use std::fmt::{self, Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use crate::generators::Generator;
+use crate::structs::Rng;
+
+/// Runs a reference and an optimized solution on generated inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Differential {
+    /// How many inputs to generate, one per seed
+    pub cases: u64,
+    /// Inputs are generated with sizes from 1 up to this
+    pub max_size: usize,
+    /// Where shrunk failing inputs are written
+    pub failure_dir: PathBuf,
+}
+
+/// An input on which the two solutions disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub name: String,
+    pub seed: u64,
+    /// The smallest failing input found by shrinking the generated one
+    pub input: String,
+    pub reference: String,
+    pub optimized: String,
+    pub path: PathBuf,
+}
+
+impl Default for Differential {
+    fn default() -> Self {
+        Differential::new(1_000, 10)
+    }
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} disagrees for seed {}: reference gave {} but optimized gave {}\nshrunk input (written to {}):\n{}",
+            self.name, self.seed, self.reference, self.optimized, self.path.display(), self.input
+        )
+    }
+}
+
+/// The result of a solution, or its panic message
+fn run<T>(solution: &impl Fn(&str) -> T, input: &str) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| solution(input))).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())
+    })
+}
+
+fn describe<T: Debug>(outcome: &Result<T, String>) -> String {
+    match outcome {
+        Ok(value) => format!("{:?}", value),
+        Err(message) => format!("a panic ({})", message),
+    }
+}
+
+/// Where shrunk inputs are written by default, `AOC_DIFFERENTIAL_DIR` or a temporary directory
+fn default_failure_dir() -> PathBuf {
+    std::env::var_os("AOC_DIFFERENTIAL_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("aoc-differential"))
+}
+
+/// Removes lines, then comma separated items and whitespace separated
+/// tokens within lines, as long as the input keeps failing
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let trailing_newline = if input.ends_with('\n') { "\n" } else { "" };
+    let join = |lines: &[String]| format!("{}{}", lines.join("\n"), trailing_newline);
+    let mut lines: Vec<String> = input.lines().map(String::from).collect();
+
+    let mut shrunk = true;
+    while shrunk {
+        shrunk = false;
+
+        for i in (0..lines.len()).rev() {
+            let mut candidate = lines.clone();
+            candidate.remove(i);
+            if fails(&join(&candidate)) {
+                lines = candidate;
+                shrunk = true;
+            }
+        }
+
+        for i in 0..lines.len() {
+            for separator in [",", " "] {
+                let mut parts: Vec<String> = lines[i].split(separator).map(String::from).collect();
+                let mut j = parts.len();
+                while parts.len() > 1 && j > 0 {
+                    j -= 1;
+                    let mut candidate_parts = parts.clone();
+                    candidate_parts.remove(j);
+                    let mut candidate = lines.clone();
+                    candidate[i] = candidate_parts.join(separator);
+                    if fails(&join(&candidate)) {
+                        parts = candidate_parts;
+                        lines = candidate;
+                        shrunk = true;
+                    }
+                }
+            }
+        }
+    }
+
+    join(&lines)
+}
+
+impl Differential {
+    pub fn new(cases: u64, max_size: usize) -> Differential {
+        Differential { cases, max_size, failure_dir: default_failure_dir() }
+    }
+
+    /// Compares both solutions on every generated input and returns the first
+    /// mismatch, shrunk. Inputs the reference panics on are considered invalid.
+    pub fn check<T: PartialEq + Debug>(
+        &self,
+        name: &str,
+        generator: Generator,
+        reference: impl Fn(&str) -> T,
+        optimized: impl Fn(&str) -> T,
+    ) -> Option<Mismatch> {
+        let fails = |input: &str| match run(&reference, input) {
+            Ok(expected) => run(&optimized, input) != Ok(expected),
+            Err(_) => false,
+        };
+
+        for seed in 0..self.cases {
+            let size = 1 + seed as usize % self.max_size.max(1);
+            let input = generator(&mut Rng::new(seed), size);
+            if !fails(&input) {
+                continue;
+            }
+
+            let input = shrink(&input, fails);
+            let path = self.failure_dir.join(format!("{}-seed-{}.txt", name.replace(' ', "-"), seed));
+            std::fs::create_dir_all(&self.failure_dir).expect("failed to create directory for failing inputs");
+            std::fs::write(&path, &input).expect("failed to write failing input");
+
+            return Some(Mismatch {
+                name: name.to_string(),
+                seed,
+                reference: describe(&run(&reference, &input)),
+                optimized: describe(&run(&optimized, &input)),
+                input,
+                path,
+            });
+        }
+        None
+    }
+
+    /// Like check, but panics with the shrunk input on a mismatch
+    pub fn assert_agree<T: PartialEq + Debug>(
+        &self,
+        name: &str,
+        generator: Generator,
+        reference: impl Fn(&str) -> T,
+        optimized: impl Fn(&str) -> T,
+    ) {
+        if let Some(mismatch) = self.check(name, generator, reference, optimized) {
+            panic!("{}", mismatch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::dial_rotations;
+
+    #[test]
+    fn test_shrink() {
+        let input = "1\n2\n3,4,5\n6 7\n";
+        let shrunk = shrink(input, |input| input.contains('4') && input.contains('7'));
+        assert_eq!(shrunk, "4\n7\n");
+    }
+
+    #[test]
+    fn test_check_agreeing_solutions() {
+        let differential = Differential::new(50, 5);
+        let count = |input: &str| input.lines().count();
+        assert_eq!(differential.check("count", dial_rotations, count, |input: &str| input.split('\n').count() - 1), None);
+    }
+
+    #[test]
+    fn test_check_shrinks_mismatch() {
+        let failure_dir = std::env::temp_dir().join("aoc-differential-test");
+        let differential = Differential { failure_dir: failure_dir.clone(), ..Differential::new(50, 5) };
+
+        // the optimized solution forgets about rotations to the left
+        let reference = |input: &str| input.lines().filter(|line| !line.is_empty()).count();
+        let optimized = |input: &str| input.lines().filter(|line| line.starts_with('R')).count();
+        let mismatch = differential.check("left", dial_rotations, reference, optimized).unwrap();
+
+        assert!(mismatch.input.starts_with('L'));
+        assert_eq!(mismatch.input.lines().count(), 1);
+        assert_eq!((mismatch.reference.as_str(), mismatch.optimized.as_str()), ("1", "0"));
+        assert!(mismatch.path.starts_with(&failure_dir));
+        assert_eq!(std::fs::read_to_string(&mismatch.path).unwrap(), mismatch.input);
+    }
+}
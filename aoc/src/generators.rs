@@ -0,0 +1,412 @@
+use std::collections::HashSet;
+
+use crate::structs::Rng;
+
+/// Generates a puzzle input of roughly the given size
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// Every puzzle with a generator, as (year, day, generator)
+pub const GENERATORS: &[(u32, u32, Generator)] = &[
+    (2023, 1, calibration_document),
+    (2023, 2, cube_games),
+    (2023, 3, engine_schematic),
+    (2023, 4, scratchcards),
+    (2023, 5, almanac),
+    (2023, 6, boat_races),
+    (2023, 7, camel_cards),
+    (2025, 1, dial_rotations),
+    (2025, 2, id_ranges),
+    (2025, 3, battery_banks),
+    (2025, 4, roll_grid),
+    (2025, 5, ingredient_database),
+    (2025, 9, tile_loop),
+];
+
+pub fn generator(year: u32, day: u32) -> Option<Generator> {
+    GENERATORS
+        .iter()
+        .find(|(y, d, _)| (*y, *d) == (year, day))
+        .map(|(_, _, generator)| *generator)
+}
+
+/// The input for a puzzle, the same for every call with the same arguments
+pub fn generate(year: u32, day: u32, size: usize, seed: u64) -> Option<String> {
+    generator(year, day).map(|generator| generator(&mut Rng::new(seed), size))
+}
+
+const DIGIT_WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+/// `size` lines of letters, digits and spelled out digits, each with at least one digit
+pub fn calibration_document(rng: &mut Rng, size: usize) -> String {
+    let mut document = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        let mut has_digit = false;
+        for _ in 0..rng.range(1..=8) {
+            match rng.below(4) {
+                0 => {
+                    line.push(char::from(b'1' + rng.below(9) as u8));
+                    has_digit = true;
+                }
+                1 => line.push_str(rng.choose::<&str>(&DIGIT_WORDS)),
+                _ => line.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        if !has_digit {
+            line.push(char::from(b'1' + rng.below(9) as u8));
+        }
+        document.push_str(&line);
+        document.push('\n');
+    }
+    document
+}
+
+/// `size` games of one to six draws with up to 20 cubes per color
+pub fn cube_games(rng: &mut Rng, size: usize) -> String {
+    let mut games = String::new();
+    for id in 1..=size {
+        let draws: Vec<String> = (0..rng.range(1..=6))
+            .map(|_| {
+                let mut colors = vec!["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                colors.truncate(rng.range(1..=3) as usize);
+                let cubes: Vec<String> = colors
+                    .iter()
+                    .map(|color| format!("{} {}", rng.range(1..=20), color))
+                    .collect();
+                cubes.join(", ")
+            })
+            .collect();
+        games.push_str(&format!("Game {}: {}\n", id, draws.join("; ")));
+    }
+    games
+}
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '=', '%', '-', '&'];
+
+/// A `size` by `size` schematic of numbers with up to three digits and symbols
+pub fn engine_schematic(rng: &mut Rng, size: usize) -> String {
+    let mut schematic = String::new();
+    for _ in 0..size {
+        let mut row = String::new();
+        while row.len() < size {
+            if rng.chance(1, 6) {
+                let len = (rng.range(1..=3) as usize).min(size - row.len());
+                row.push_str(&rng.range(10u64.pow(len as u32 - 1)..=10u64.pow(len as u32) - 1).to_string());
+                // keep the next number apart from this one
+                if row.len() < size {
+                    row.push(if rng.chance(1, 4) { *rng.choose(&SYMBOLS) } else { '.' });
+                }
+            } else if rng.chance(1, 10) {
+                row.push(*rng.choose(&SYMBOLS));
+            } else {
+                row.push('.');
+            }
+        }
+        schematic.push_str(&row);
+        schematic.push('\n');
+    }
+    schematic
+}
+
+/// `size` cards with ten winning numbers and 25 numbers you have.
+///
+/// Most cards win nothing or once, but the number of copies in part
+/// two still grows quickly with the size.
+pub fn scratchcards(rng: &mut Rng, size: usize) -> String {
+    let mut cards = String::new();
+    for id in 1..=size {
+        let mut numbers: Vec<u64> = (1..=99).collect();
+        rng.shuffle(&mut numbers);
+        let wins = match rng.below(10) {
+            0..=5 => 0,
+            6 | 7 => 1,
+            _ => rng.range(2..=10) as usize,
+        };
+        let winning = &numbers[..10];
+        let mut have: Vec<u64> = numbers[..wins].iter().chain(&numbers[10..35 - wins]).copied().collect();
+        rng.shuffle(&mut have);
+
+        let format = |numbers: &[u64]| -> String {
+            numbers.iter().map(|number| format!("{:>2}", number)).collect::<Vec<String>>().join(" ")
+        };
+        cards.push_str(&format!("Card {:>3}: {} | {}\n", id, format(winning), format(&have)));
+    }
+    cards
+}
+
+const ALMANAC_MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// `size` seed ranges of up to 100 seeds and seven maps of `size` ranges each.
+///
+/// Each map moves disjoint source ranges onto disjoint target ranges,
+/// leaving some ids between them untouched.
+pub fn almanac(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let domain = 1_000 * size as u64;
+
+    let seeds: Vec<String> = (0..size)
+        .map(|_| format!("{} {}", rng.range(0..=domain - 100), rng.range(1..=100)))
+        .collect();
+    let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+
+    for name in ALMANAC_MAPS {
+        let mut cuts: Vec<u64> = (0..=size).map(|_| rng.range(0..=domain)).collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let pieces: Vec<[u64; 2]> = cuts
+            .windows(2)
+            .map(|pair| [pair[0], pair[1] - pair[0]])
+            .filter(|_| rng.chance(4, 5))
+            .collect();
+
+        // lay the pieces out again in a different order, somewhere else
+        let mut order: Vec<usize> = (0..pieces.len()).collect();
+        rng.shuffle(&mut order);
+        let mut target = rng.range(0..=domain);
+        let mut targets = vec![0; pieces.len()];
+        for &i in &order {
+            targets[i] = target;
+            target += pieces[i][1];
+        }
+
+        almanac.push_str(&format!("\n{} map:\n", name));
+        for (piece, target) in pieces.iter().zip(targets) {
+            almanac.push_str(&format!("{} {} {}\n", target, piece[0], piece[1]));
+        }
+    }
+    almanac
+}
+
+/// `size` races that can all be won.
+///
+/// Part two joins the numbers of all races, which only fits
+/// into a u64 for about four races.
+pub fn boat_races(rng: &mut Rng, size: usize) -> String {
+    let races: Vec<(u64, u64)> = (0..size)
+        .map(|_| {
+            let time = rng.range(7..=99);
+            (time, rng.range(1..=time * time / 4 - 1))
+        })
+        .collect();
+    let times: String = races.iter().map(|(time, _)| format!("{:>5}", time)).collect();
+    let distances: String = races.iter().map(|(_, distance)| format!("{:>5}", distance)).collect();
+    format!("Time:    {}\nDistance:{}\n", times, distances)
+}
+
+const CARD_LABELS: [char; 13] = ['A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// `size` distinct hands with bids up to 1000
+pub fn camel_cards(rng: &mut Rng, size: usize) -> String {
+    let size = size.min(CARD_LABELS.len().pow(5));
+    let mut seen = HashSet::new();
+    let mut hands = String::new();
+    while seen.len() < size {
+        // drawing from fewer labels makes pairs and better hands more likely
+        let mut labels = CARD_LABELS.to_vec();
+        rng.shuffle(&mut labels);
+        labels.truncate(rng.range(1..=5) as usize);
+        let hand: String = (0..5).map(|_| *rng.choose(&labels)).collect();
+        if seen.insert(hand.clone()) {
+            hands.push_str(&format!("{} {}\n", hand, rng.range(1..=1000)));
+        }
+    }
+    hands
+}
+
+/// `size` rotations of the dial by up to 999 clicks
+pub fn dial_rotations(rng: &mut Rng, size: usize) -> String {
+    let mut rotations = String::new();
+    for _ in 0..size {
+        let direction = if rng.chance(1, 2) { 'L' } else { 'R' };
+        rotations.push_str(&format!("{}{}\n", direction, rng.range(1..=999)));
+    }
+    rotations
+}
+
+/// `size` id ranges starting at ids of up to ten digits and ending
+/// at most `1000 * size` ids after their start
+pub fn id_ranges(rng: &mut Rng, size: usize) -> String {
+    let ranges: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1..=10) as u32;
+            let start = id_with_digits(rng, digits);
+            format!("{}-{}", start, start + rng.range(0..=1_000 * size as u64))
+        })
+        .collect();
+    format!("{}\n", ranges.join(","))
+}
+
+/// A positive id with at most the given number of digits
+fn id_with_digits(rng: &mut Rng, digits: u32) -> u64 {
+    rng.range(1..=10u64.pow(digits) - 1)
+}
+
+/// `size` banks of 100 batteries with joltages from 1 to 9
+pub fn battery_banks(rng: &mut Rng, size: usize) -> String {
+    let mut banks = String::new();
+    for _ in 0..size {
+        let bank: String = (0..100).map(|_| char::from(b'1' + rng.below(9) as u8)).collect();
+        banks.push_str(&bank);
+        banks.push('\n');
+    }
+    banks
+}
+
+/// A `size` by `size` grid where three in five cells hold a roll
+pub fn roll_grid(rng: &mut Rng, size: usize) -> String {
+    let mut grid = String::new();
+    for _ in 0..size {
+        let row: String = (0..size).map(|_| if rng.chance(3, 5) { '@' } else { '.' }).collect();
+        grid.push_str(&row);
+        grid.push('\n');
+    }
+    grid
+}
+
+/// `size` possibly overlapping fresh ranges followed by `size` ids,
+/// about half of which are picked from the ranges
+pub fn ingredient_database(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let ranges: Vec<[u64; 2]> = (0..size)
+        .map(|_| {
+            let start = rng.range(1..=100_000_000_000_000);
+            [start, start + rng.range(0..=1_000_000_000_000)]
+        })
+        .collect();
+    let mut database: String = ranges.iter().map(|range| format!("{}-{}\n", range[0], range[1])).collect();
+    database.push('\n');
+    for _ in 0..size {
+        let id = if rng.chance(1, 2) {
+            let range = rng.choose(&ranges);
+            rng.range(range[0]..=range[1])
+        } else {
+            rng.range(1..=101_000_000_000_000)
+        };
+        database.push_str(&format!("{}\n", id));
+    }
+    database
+}
+
+/// A loop of about `size` red tiles shaped like a skyline above and below a common band.
+///
+/// Walking left to right along the top and back along the bottom, every
+/// column range overlaps its neighbours, so the loop never touches itself.
+/// Columns are often a single tile wide, which puts parallel edges right next
+/// to each other with no tiles between them.
+pub fn tile_loop(rng: &mut Rng, size: usize) -> String {
+    let columns = (size / 4).max(1);
+    let mut xs = vec![rng.range(0..=1_000)];
+    for _ in 0..columns {
+        let last = xs[xs.len() - 1];
+        let width = if rng.chance(1, 3) { 1 } else { rng.range(1..=1_000) };
+        xs.push(last + width);
+    }
+
+    // neighbouring tops and bottoms differ, so no red tile lies on a straight edge
+    let mut next_differing = |range: std::ops::RangeInclusive<u64>, previous: Option<u64>| loop {
+        let value = match previous {
+            Some(previous) if rng.chance(1, 3) => {
+                if rng.chance(1, 2) { previous + 1 } else { previous.wrapping_sub(1) }
+            }
+            _ => rng.range(range.clone()),
+        };
+        if previous != Some(value) && range.contains(&value) {
+            return value;
+        }
+    };
+    let mut tops: Vec<u64> = vec![];
+    let mut bottoms: Vec<u64> = vec![];
+    for _ in 0..columns {
+        tops.push(next_differing(50_001..=100_000, tops.last().copied()));
+        bottoms.push(next_differing(0..=49_999, bottoms.last().copied()));
+    }
+
+    let mut tiles: Vec<[u64; 2]> = vec![];
+    for i in 0..columns {
+        tiles.push([xs[i], tops[i]]);
+        tiles.push([xs[i + 1], tops[i]]);
+    }
+    for i in (0..columns).rev() {
+        tiles.push([xs[i + 1], bottoms[i]]);
+        tiles.push([xs[i], bottoms[i]]);
+    }
+    tiles.iter().map(|tile| format!("{},{}\n", tile[0], tile[1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        for &(year, day, _) in GENERATORS {
+            let input = generate(year, day, 20, 3).unwrap();
+            assert!(!input.is_empty(), "{} day {}", year, day);
+            assert_eq!(generate(year, day, 20, 3), Some(input.clone()), "{} day {}", year, day);
+            assert_ne!(generate(year, day, 20, 4), Some(input), "{} day {}", year, day);
+        }
+        assert_eq!(generate(2024, 1, 20, 3), None);
+    }
+
+    #[test]
+    fn test_id_digits() {
+        let mut rng = Rng::new(5);
+        for digits in 1..=10 {
+            for _ in 0..1_000 {
+                let id = id_with_digits(&mut rng, digits);
+                assert!(id > 0 && id.to_string().len() <= digits as usize, "{} for {} digits", id, digits);
+            }
+        }
+
+        let ranges = id_ranges(&mut rng, 500);
+        for range in ranges.trim().split(',') {
+            let (start, end) = range.split_once('-').unwrap();
+            let (start, end): (u64, u64) = (start.parse().unwrap(), end.parse().unwrap());
+            assert!(start.to_string().len() <= 10 && start <= end && end - start <= 500_000, "{}", range);
+        }
+    }
+
+    #[test]
+    fn test_tile_loop_is_rectilinear() {
+        let input = tile_loop(&mut Rng::new(1), 40);
+        let tiles: Vec<Vec<u64>> = input
+            .lines()
+            .map(|line| line.split(',').map(|n| n.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(tiles.len(), 40);
+        for (i, a) in tiles.iter().enumerate() {
+            let b = &tiles[(i + 1) % tiles.len()];
+            assert!((a[0] == b[0]) != (a[1] == b[1]), "{:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let mut rng = Rng::new(9);
+        let schematic = engine_schematic(&mut rng, 30);
+        assert!(schematic.lines().all(|row| row.len() == 30));
+        assert_eq!(schematic.lines().count(), 30);
+
+        let cards = scratchcards(&mut rng, 5);
+        for card in cards.lines() {
+            let (_, numbers) = card.split_once(": ").unwrap();
+            let (winning, have) = numbers.split_once(" | ").unwrap();
+            assert_eq!(winning.split_whitespace().count(), 10);
+            assert_eq!(have.split_whitespace().count(), 25);
+        }
+
+        let races = boat_races(&mut rng, 3);
+        assert!(races.starts_with("Time:") && races.contains("\nDistance:"));
+        assert!(calibration_document(&mut rng, 50).lines().all(|line| line.chars().any(|c| c.is_ascii_digit())));
+        assert_eq!(camel_cards(&mut rng, 100).lines().collect::<HashSet<&str>>().len(), 100);
+    }
+}
@@ -0,0 +1,9 @@
+mod differential;
+mod generators;
+mod stream;
+mod structs;
+
+pub use crate::differential::*;
+pub use crate::generators::*;
+pub use crate::stream::*;
+pub use crate::structs::*;
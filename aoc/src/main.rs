@@ -0,0 +1,71 @@
+use aoc::*;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: aoc gen --year Y --day D [--size N] [--seed S]";
+
+/// The value following a flag, e.g. the 2025 in `--year 2025`
+fn flag_value<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>, String> {
+    match args.iter().position(|arg| arg == flag) {
+        None => Ok(None),
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|value| value.parse().ok())
+            .map(Some)
+            .ok_or_else(|| format!("{} should be followed by a number", flag)),
+    }
+}
+
+/// The generated puzzle input for the arguments, e.g.
+/// `gen --year 2025 --day 4 --size 1000 --seed 7`
+fn run(args: &[String]) -> Result<String, String> {
+    if args.first().map(String::as_str) != Some("gen") {
+        return Err(USAGE.to_string());
+    }
+    let year: u32 = flag_value(args, "--year")?.ok_or("gen needs a --year")?;
+    let day: u32 = flag_value(args, "--day")?.ok_or("gen needs a --day")?;
+    let size: usize = flag_value(args, "--size")?.unwrap_or(100);
+    let seed: u64 = flag_value(args, "--seed")?.unwrap_or(0);
+
+    generate(year, day, size, seed).ok_or_else(|| {
+        let days: Vec<String> = GENERATORS
+            .iter()
+            .map(|(year, day, _)| format!("{} day {}", year, day))
+            .collect();
+        format!("there is no generator for {} day {}, only for {}", year, day, days.join(", "))
+    })
+}
+
+/// Prints a generated puzzle input, or the problem with the arguments
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(input) => {
+            print!("{}", input);
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(run(&args("gen --year 2025 --day 1 --size 3 --seed 2")), Ok(generate(2025, 1, 3, 2).unwrap()));
+        assert_eq!(run(&args("")), Err(USAGE.to_string()));
+        assert_eq!(run(&args("gen --day 1")), Err("gen needs a --year".to_string()));
+        assert_eq!(run(&args("gen --year 2025 --day x")), Err("--day should be followed by a number".to_string()));
+
+        let error = run(&args("gen --year 2024 --day 1")).unwrap_err();
+        assert!(error.starts_with("there is no generator for 2024 day 1, only for 2023 day 1, 2023 day 2"));
+    }
+}
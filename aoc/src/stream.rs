@@ -0,0 +1,50 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// Opens the input file for streaming, or standard input for "-"
+pub fn open_stream(path: &str) -> Box<dyn BufRead> {
+    if path == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(path).expect("failed to open input file")))
+    }
+}
+
+/// Handles `--stream <path|->`: solves both parts with a single pass over the
+/// lines of the input, so it never has to fit into memory, and prints them.
+/// Returns whether the flag was given.
+pub fn run_streaming<A: Display, B: Display>(
+    args: &[String],
+    solve: impl FnOnce(Box<dyn BufRead>) -> (A, B),
+) -> bool {
+    let Some(i) = args.iter().position(|arg| arg == "--stream") else {
+        return false;
+    };
+    let path = args.get(i + 1).expect("--stream should be followed by a path or -");
+    let (solution1, solution2) = solve(open_stream(path));
+    println!("Solution to puzzle one: {}", solution1);
+    println!("Solution to puzzle two: {}", solution2);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_streaming() {
+        let path = std::env::temp_dir().join("aoc-stream-test.txt");
+        std::fs::write(&path, "1\n2\n3\n").unwrap();
+        let args = vec!["--stream".to_string(), path.display().to_string()];
+
+        let mut lines = 0;
+        let count = |reader: Box<dyn BufRead>| {
+            lines = reader.lines().count();
+            (lines, 0)
+        };
+        assert!(run_streaming(&args, count));
+        assert_eq!(lines, 3);
+        assert!(!run_streaming(&[], |_| (0, 0)));
+    }
+}
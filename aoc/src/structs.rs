@@ -0,0 +1,90 @@
+use std::ops::RangeInclusive;
+
+/// A small seeded random number generator (SplitMix64).
+///
+/// Generated inputs have to stay the same for a seed across
+/// toolchains and dependency updates, so it is implemented here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed number in the range
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = (*range.start(), *range.end());
+        assert!(start <= end, "range should not be empty");
+        match (end - start).checked_add(1) {
+            Some(len) => start + ((self.next_u64() as u128 * len as u128) >> 64) as u64,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below n
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "there should be something to pick from");
+        self.range(0..=n as u64 - 1) as usize
+    }
+
+    /// True with probability numerator / denominator
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(1..=denominator) <= numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let numbers: Vec<u64> = (0..5).map({
+            let mut rng = Rng::new(42);
+            move |_| rng.next_u64()
+        }).collect();
+        let again: Vec<u64> = (0..5).map({
+            let mut rng = Rng::new(42);
+            move |_| rng.next_u64()
+        }).collect();
+        assert_eq!(numbers, again);
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_rng_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!((3..=9).contains(&rng.range(3..=9)));
+            assert_eq!(rng.range(5..=5), 5);
+            assert!(rng.below(4) < 4);
+        }
+        rng.range(0..=u64::MAX);
+
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<usize>>());
+    }
+}